@@ -0,0 +1,203 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::diff::{CrashCategory, display::Verdict};
+
+/// Maximum number of stderr lines kept in a signature.
+const MAX_KEY_LINES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum FindingKind {
+    Crash(Option<CrashCategory>),
+    Mismatch(Verdict),
+}
+
+impl FindingKind {
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            FindingKind::Crash(Some(category)) => format!("crash ({})", category.name()),
+            FindingKind::Crash(None) => "crash".to_string(),
+            FindingKind::Mismatch(verdict) => format!("mismatch ({})", verdict.describe()),
+        }
+    }
+}
+
+/// What two findings must share to be considered the same bug.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Signature {
+    pub(crate) kind: FindingKind,
+    /// Spec of the failing solver, or of both solvers for a mismatch
+    pub(crate) solver: String,
+    /// Normalised key lines of the error message, or the model for a mismatch
+    pub(crate) key_lines: Vec<String>,
+}
+
+/// Replaces the parts of a line that vary between runs of the same bug: paths, line and
+/// column numbers, addresses and other numbers.
+fn normalise(line: &str) -> String {
+    let words: Vec<String> = line
+        .split_whitespace()
+        .map(|word| {
+            let lower = word.to_lowercase();
+            let is_path = word.contains('/')
+                || [".mzn", ".dzn", ".fzn", ".ozn", ".mzc", ".json"]
+                    .iter()
+                    .any(|ext| lower.contains(ext));
+            if is_path {
+                return "<path>".to_string();
+            }
+            if lower.starts_with("0x") {
+                return "<addr>".to_string();
+            }
+            let mut normalised = String::new();
+            for c in word.chars() {
+                if !c.is_ascii_digit() {
+                    normalised.push(c);
+                } else if !normalised.ends_with('N') {
+                    normalised.push('N');
+                }
+            }
+            normalised
+        })
+        .collect();
+    words.join(" ")
+}
+
+/// Picks the lines of an error message that identify the error, normalised. Falls back to
+/// the last line if none of them looks like an error.
+pub(crate) fn key_lines(message: &str) -> Vec<String> {
+    let markers = [
+        "error",
+        "exception",
+        "assert",
+        "abort",
+        "fault",
+        "signal",
+        "terminate",
+    ];
+    let lines: Vec<&str> = message
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let mut keys: Vec<String> = Vec::new();
+    for line in &lines {
+        let lower = line.to_lowercase();
+        if markers.iter().any(|m| lower.contains(m)) {
+            let key = normalise(line);
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+            if keys.len() == MAX_KEY_LINES {
+                break;
+            }
+        }
+    }
+    if keys.is_empty()
+        && let Some(last) = lines.last()
+    {
+        keys.push(normalise(last));
+    }
+    keys
+}
+
+/// An instance on which a finding was made.
+pub(crate) struct Reproducer {
+    pub(crate) instance: PathBuf,
+    /// `minizinc-diff` command line reproducing the finding
+    pub(crate) command: String,
+    /// Folder saved for the finding with `--save-findings`
+    pub(crate) bundle: Option<PathBuf>,
+    /// Logs of the runs written with `--log-dir`
+    pub(crate) logs: Vec<PathBuf>,
+}
+
+/// Findings sharing the same signature.
+pub(crate) struct Bucket {
+    pub(crate) signature: Signature,
+    pub(crate) count: usize,
+    /// The first finding of the bucket
+    pub(crate) representative: Reproducer,
+}
+
+/// Buckets of findings, in the order they were first found.
+#[derive(Default)]
+pub(crate) struct Buckets {
+    buckets: Vec<Bucket>,
+    index: HashMap<Signature, usize>,
+}
+
+impl Buckets {
+    /// Adds a finding and returns the index of its bucket, along with whether the bucket
+    /// is new.
+    pub(crate) fn add(&mut self, signature: Signature, reproducer: Reproducer) -> (usize, bool) {
+        if let Some(&i) = self.index.get(&signature) {
+            self.buckets[i].count += 1;
+            return (i, false);
+        }
+        let i = self.buckets.len();
+        self.index.insert(signature.clone(), i);
+        self.buckets.push(Bucket {
+            signature,
+            count: 1,
+            representative: reproducer,
+        });
+        (i, true)
+    }
+
+    pub(crate) fn set_bundle(&mut self, i: usize, bundle: PathBuf) {
+        self.buckets[i].representative.bundle = Some(bundle);
+    }
+
+    pub(crate) fn as_slice(&self) -> &[Bucket] {
+        &self.buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalise_strips_paths_addresses_and_numbers() {
+        assert_eq!(
+            normalise("Error: /tmp/model.mzn:12.3-8: assertion failed at 0x7ffd1234"),
+            "Error: <path> assertion failed at <addr>"
+        );
+        assert_eq!(
+            normalise("signal 11 after 2000 nodes"),
+            "signal N after N nodes"
+        );
+    }
+
+    #[test]
+    fn key_lines_pick_error_lines() {
+        assert_eq!(
+            key_lines("Flattening...\nMiniZinc: type error: undefined identifier `x1`\n"),
+            vec!["MiniZinc: type error: undefined identifier `xN`"]
+        );
+    }
+
+    #[test]
+    fn key_lines_fall_back_to_the_last_line() {
+        assert_eq!(
+            key_lines("first line\n\nstopped after 42 nodes\n"),
+            vec!["stopped after N nodes"]
+        );
+    }
+}
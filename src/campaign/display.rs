@@ -0,0 +1,188 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::path::Path;
+
+use crate::{campaign::bucket::Bucket, diff::display::OutputMode};
+
+/// What came out of checking one instance.
+pub(crate) enum InstanceResult {
+    Passed,
+    /// A run did not finish, but nothing wrong was found.
+    Inconclusive,
+    /// A model error was ignored and nothing else was found.
+    ModelError,
+    /// Index of the bucket of every finding, along with whether the bucket is new
+    Findings(Vec<(usize, bool)>),
+}
+
+#[derive(Default)]
+pub(crate) struct Tally {
+    pub(crate) instances: usize,
+    pub(crate) passed: usize,
+    pub(crate) inconclusive: usize,
+    pub(crate) model_errors: usize,
+    pub(crate) with_findings: usize,
+}
+
+impl Tally {
+    pub(crate) fn record(&mut self, result: &InstanceResult) {
+        self.instances += 1;
+        match result {
+            InstanceResult::Passed => self.passed += 1,
+            InstanceResult::Inconclusive => self.inconclusive += 1,
+            InstanceResult::ModelError => self.model_errors += 1,
+            InstanceResult::Findings(_) => self.with_findings += 1,
+        }
+    }
+}
+
+fn bucket_list(found: &[(usize, bool)]) -> String {
+    let items: Vec<String> = found
+        .iter()
+        .map(|(i, is_new)| {
+            if *is_new {
+                format!("#{} (new)", i + 1)
+            } else {
+                format!("#{}", i + 1)
+            }
+        })
+        .collect();
+    items.join(", ")
+}
+
+pub(crate) fn print_progress(
+    output: OutputMode,
+    (n, total): (usize, usize),
+    instance: &Path,
+    result: &InstanceResult,
+) {
+    let instance = instance.display();
+    match (output, result) {
+        (OutputMode::Ascii, InstanceResult::Passed) => {
+            println!("[{n}/{total}] [OK] {instance}")
+        }
+        (OutputMode::Ascii, InstanceResult::Inconclusive) => {
+            println!("[{n}/{total}] [INCONCLUSIVE] {instance}")
+        }
+        (OutputMode::Ascii, InstanceResult::ModelError) => {
+            println!("[{n}/{total}] [MODEL-ERROR] {instance}")
+        }
+        (OutputMode::Ascii, InstanceResult::Findings(found)) => {
+            println!("[{n}/{total}] [FAIL] {instance}: {}", bucket_list(found))
+        }
+        (OutputMode::Rich, InstanceResult::Passed) => {
+            println!("\x1b[2m[{n}/{total}]\x1b[0m \x1b[32m✅ {instance}\x1b[0m")
+        }
+        (OutputMode::Rich, InstanceResult::Inconclusive) => {
+            println!("\x1b[2m[{n}/{total}]\x1b[0m \x1b[33m⏳ {instance}\x1b[0m")
+        }
+        (OutputMode::Rich, InstanceResult::ModelError) => {
+            println!("\x1b[2m[{n}/{total}]\x1b[0m \x1b[33m📝 {instance}\x1b[0m")
+        }
+        (OutputMode::Rich, InstanceResult::Findings(found)) => println!(
+            "\x1b[2m[{n}/{total}]\x1b[0m \x1b[31m❌ {instance}: \x1b[1m{}\x1b[0m",
+            bucket_list(found)
+        ),
+        (OutputMode::None, _) => {}
+    }
+}
+
+fn print_summary_ascii(tally: &Tally, buckets: &[Bucket]) {
+    println!(
+        "[DONE] {} instances: {} passed, {} inconclusive, {} with ignored model errors, {} with findings",
+        tally.instances, tally.passed, tally.inconclusive, tally.model_errors, tally.with_findings
+    );
+    if buckets.is_empty() {
+        println!("[OK] No findings");
+        return;
+    }
+    let n_findings: usize = buckets.iter().map(|b| b.count).sum();
+    println!(
+        "[FAIL] {n_findings} findings in {} unique buckets:",
+        buckets.len()
+    );
+    for (i, bucket) in buckets.iter().enumerate() {
+        println!(
+            "#{} {}x {} of `{}`",
+            i + 1,
+            bucket.count,
+            bucket.signature.kind.describe(),
+            bucket.signature.solver
+        );
+        for line in &bucket.signature.key_lines {
+            println!("    {line}");
+        }
+        println!(
+            "    first found on {}: {}",
+            bucket.representative.instance.display(),
+            bucket.representative.command
+        );
+        if let Some(bundle) = &bucket.representative.bundle {
+            println!("    saved to {}", bundle.display());
+        }
+        for log in &bucket.representative.logs {
+            println!("    logged to {}", log.display());
+        }
+    }
+}
+
+fn print_summary_rich(tally: &Tally, buckets: &[Bucket]) {
+    println!(
+        "\x1b[1m🏁 {} instances:\x1b[0m \x1b[32m{} passed\x1b[0m, \x1b[33m{} inconclusive\x1b[0m, \x1b[33m{} with ignored model errors\x1b[0m, \x1b[31m{} with findings\x1b[0m",
+        tally.instances, tally.passed, tally.inconclusive, tally.model_errors, tally.with_findings
+    );
+    if buckets.is_empty() {
+        println!("\x1b[32m✅ No findings\x1b[0m");
+        return;
+    }
+    let n_findings: usize = buckets.iter().map(|b| b.count).sum();
+    println!(
+        "\x1b[31m❌ \x1b[1m{n_findings}\x1b[0m\x1b[31m findings in \x1b[1m{}\x1b[0m\x1b[31m unique buckets:\x1b[0m",
+        buckets.len()
+    );
+    for (i, bucket) in buckets.iter().enumerate() {
+        println!(
+            "\x1b[1m#{}\x1b[0m \x1b[31m{}×\x1b[0m {} of \x1b[1m{}\x1b[0m",
+            i + 1,
+            bucket.count,
+            bucket.signature.kind.describe(),
+            bucket.signature.solver
+        );
+        for line in &bucket.signature.key_lines {
+            println!("    \x1b[2m{line}\x1b[0m");
+        }
+        println!(
+            "    🔁 \x1b[2mfirst found on {}:\x1b[0m {}",
+            bucket.representative.instance.display(),
+            bucket.representative.command
+        );
+        if let Some(bundle) = &bucket.representative.bundle {
+            println!("    💾 \x1b[2msaved to\x1b[0m {}", bundle.display());
+        }
+        for log in &bucket.representative.logs {
+            println!("    📄 \x1b[2mlogged to\x1b[0m {}", log.display());
+        }
+    }
+}
+
+pub(crate) fn print_summary(output: OutputMode, tally: &Tally, buckets: &[Bucket]) {
+    match output {
+        OutputMode::Ascii => print_summary_ascii(tally, buckets),
+        OutputMode::Rich => print_summary_rich(tally, buckets),
+        OutputMode::None => {}
+    }
+}
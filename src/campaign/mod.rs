@@ -0,0 +1,283 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+mod bucket;
+mod display;
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+use clap::Args;
+
+use crate::{
+    campaign::{
+        bucket::{Buckets, FindingKind, Reproducer, Signature, key_lines},
+        display::{InstanceResult, Tally, print_progress, print_summary},
+    },
+    diff::{
+        CrashCategory, FailureKind, ResourceLimits, RunOptions, SolverOutput, SolverSpec,
+        check_solvers,
+        display::{CheckStatus, OutputMode, ReportOptions, print_diff, report_minizinc},
+        findings::Finding,
+        minizinc::{describe_command, solver_command},
+        parse_solver_spec, run_pair, single_solution_solvers,
+    },
+};
+
+#[derive(Args, Debug)]
+pub(crate) struct CampaignArgs {
+    /// Directory of models and instances, as written by `generate`
+    dir: PathBuf,
+    /// Solver tag used for the first solver run (displayed on the left), followed by --flags if needed
+    #[arg(value_parser = parse_solver_spec)]
+    solver_left: SolverSpec,
+    /// Solver tag used for the second solver run (displayed on the right), followed by --flags if needed
+    #[arg(value_parser = parse_solver_spec)]
+    solver_right: SolverSpec,
+    /// Timeout for every run
+    #[arg(short, long)]
+    timeout_secs: Option<u64>,
+    /// Disables all output
+    #[arg(short, long)]
+    quiet: bool,
+    /// `minizinc` executable used by the solvers whose spec does not give one with `tag@path`
+    #[arg(long, value_name = "PATH")]
+    minizinc: Option<PathBuf>,
+    /// Address-space limit of each run, in MiB
+    #[arg(long, value_name = "MIB")]
+    mem_limit: Option<u64>,
+    /// CPU time limit of each run, in seconds
+    #[arg(long, value_name = "SECS")]
+    cpu_limit: Option<u64>,
+    /// Limit on the output of each run, in MiB
+    #[arg(long, value_name = "MIB")]
+    output_limit: Option<u64>,
+    /// Does not report syntax, type and evaluation errors in the models as findings
+    #[arg(long)]
+    ignore_model_errors: bool,
+    /// Saves a folder with everything needed to reproduce the first finding of every bucket to this directory
+    #[arg(long, value_name = "DIR")]
+    save_findings: Option<PathBuf>,
+    /// Logs the command line, output, exit status and timestamps of every run to this directory
+    #[arg(long, value_name = "DIR")]
+    log_dir: Option<PathBuf>,
+    /// Prints the command of every run, with the MiniZinc environment variables, to stderr
+    #[arg(short, long)]
+    verbose: bool,
+    /// Prints the commands of all runs without running them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// Finds every instance of the campaign: each `.dzn` file next to a `model.mzn`, in any
+/// subdirectory of `dir`.
+fn find_instances(dir: &Path) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+
+    let mut instances = Vec::new();
+    let model = dir.join("model.mzn");
+    if model.is_file() {
+        for path in &entries {
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "dzn") {
+                instances.push((model.clone(), path.clone()));
+            }
+        }
+    }
+    for path in &entries {
+        if path.is_dir() {
+            instances.extend(find_instances(path)?);
+        }
+    }
+    Ok(instances)
+}
+
+impl CampaignArgs {
+    /// Command line of `diff` with the same solvers and limits on the given instance.
+    fn diff_command(&self, model: &Path, instance: &Path) -> String {
+        let mut words = vec![
+            "minizinc-diff".to_string(),
+            "diff".to_string(),
+            model.display().to_string(),
+            instance.display().to_string(),
+            self.solver_left.describe(),
+            self.solver_right.describe(),
+        ];
+        for (flag, value) in [
+            ("--timeout-secs", self.timeout_secs),
+            ("--mem-limit", self.mem_limit),
+            ("--cpu-limit", self.cpu_limit),
+            ("--output-limit", self.output_limit),
+        ] {
+            if let Some(value) = value {
+                words.push(flag.to_string());
+                words.push(value.to_string());
+            }
+        }
+        shell_words::join(words)
+    }
+}
+
+pub(crate) fn run(mut args: CampaignArgs) -> ExitCode {
+    args.solver_left.default_minizinc(args.minizinc.as_deref());
+    args.solver_right.default_minizinc(args.minizinc.as_deref());
+    let output_mode = OutputMode::new(args.quiet);
+    if let Err(status) = check_solvers(&mut args.solver_left, &mut args.solver_right, output_mode) {
+        return status.into();
+    }
+    let instances = match find_instances(&args.dir) {
+        Ok(instances) => instances,
+        Err(e) => {
+            eprintln!("Failed to read {}: {e}", args.dir.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let options = RunOptions {
+        timeout: args.timeout_secs.map(Duration::from_secs),
+        limits: ResourceLimits::from_mib(args.mem_limit, args.cpu_limit, args.output_limit),
+        keep_output: args.save_findings.is_some(),
+        log_dir: args.log_dir.clone(),
+        verbose: args.verbose,
+        ..RunOptions::default()
+    };
+    if args.dry_run {
+        for (model, instance) in &instances {
+            for solver in [&args.solver_left, &args.solver_right] {
+                println!(
+                    "{}",
+                    describe_command(&solver_command(model, instance, solver, &options))
+                );
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+    report_minizinc(&args.solver_left, &args.solver_right, output_mode);
+
+    let report_options = ReportOptions {
+        single_solution: single_solution_solvers(&args.solver_left, &args.solver_right),
+        ..ReportOptions::default()
+    };
+
+    let mut buckets = Buckets::default();
+    let mut tally = Tally::default();
+    for (i, (model, instance)) in instances.iter().enumerate() {
+        let (left, right, _) = run_pair(
+            model,
+            instance,
+            args.solver_left.clone(),
+            args.solver_right.clone(),
+            &options,
+            false,
+        );
+        let status = print_diff(&left, &right, OutputMode::None, &report_options);
+
+        let mut signatures = Vec::new();
+        let mut has_model_error = false;
+        for (output, spec) in [(&left, &args.solver_left), (&right, &args.solver_right)] {
+            let SolverOutput::Failed(e, _) = output else {
+                continue;
+            };
+            if e.kind != FailureKind::Crash {
+                continue;
+            }
+            if args.ignore_model_errors && e.category.is_some_and(CrashCategory::is_model_error) {
+                has_model_error = true;
+                continue;
+            }
+            signatures.push(Signature {
+                kind: FindingKind::Crash(e.category),
+                solver: spec.describe(),
+                key_lines: key_lines(&e.message),
+            });
+        }
+        if let Some(verdict) = status.verdict {
+            signatures.push(Signature {
+                kind: FindingKind::Mismatch(verdict),
+                solver: format!(
+                    "{} vs {}",
+                    args.solver_left.describe(),
+                    args.solver_right.describe()
+                ),
+                // Mismatches carry no error message, so the model tells them apart instead.
+                key_lines: vec![format!("model: {}", model.display())],
+            });
+        }
+
+        let result = if !signatures.is_empty() {
+            let found: Vec<(usize, bool)> = signatures
+                .into_iter()
+                .map(|signature| {
+                    let reproducer = Reproducer {
+                        instance: instance.clone(),
+                        command: args.diff_command(model, instance),
+                        bundle: None,
+                        logs: [&left, &right]
+                            .into_iter()
+                            .filter_map(|output| output.run().log.clone())
+                            .collect(),
+                    };
+                    buckets.add(signature, reproducer)
+                })
+                .collect();
+            if let Some(dir) = &args.save_findings
+                && found.iter().any(|(_, is_new)| *is_new)
+            {
+                let finding = Finding {
+                    model,
+                    instance,
+                    checker: None,
+                    compiled: None,
+                    solvers: (&args.solver_left, &args.solver_right),
+                    runs: (&left, &right),
+                    status,
+                    limits: options.limits,
+                    timeout: options.timeout,
+                };
+                match finding.save(dir) {
+                    Ok(path) => {
+                        for &(i, is_new) in &found {
+                            if is_new {
+                                buckets.set_bundle(i, path.clone());
+                            }
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to save the finding to {}: {e}", dir.display()),
+                }
+            }
+            InstanceResult::Findings(found)
+        } else if has_model_error {
+            InstanceResult::ModelError
+        } else if status == CheckStatus::SUCCESS {
+            InstanceResult::Passed
+        } else {
+            InstanceResult::Inconclusive
+        };
+        tally.record(&result);
+        print_progress(output_mode, (i + 1, instances.len()), instance, &result);
+    }
+
+    print_summary(output_mode, &tally, buckets.as_slice());
+    if buckets.as_slice().is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
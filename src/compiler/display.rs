@@ -0,0 +1,57 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::path::Path;
+
+use crate::diff::{SolverError, display::OutputMode};
+
+pub(crate) fn report_compile_failure(label: &str, e: &SolverError, output: OutputMode) {
+    let label = match e.describe() {
+        Some(description) => format!("{label} ({description})"),
+        None => label.to_string(),
+    };
+    match output {
+        OutputMode::Ascii => println!(
+            "[FLATZINC] Compilation failed for {label}, skipping the FlatZinc comparison: {}",
+            e.message.trim_end()
+        ),
+        OutputMode::Rich => println!(
+            "\x1b[33m🧱 Compilation failed for \x1b[1m{label}\x1b[0m\x1b[33m, skipping the FlatZinc comparison:\x1b[0m {}",
+            e.message.trim_end()
+        ),
+        OutputMode::None => {}
+    }
+}
+
+/// Tells whether a mismatch is more likely due to flattening or to solving, from whether
+/// the FlatZinc of both runs differs.
+pub(crate) fn report_origin(flatzinc_differs: Option<bool>, dir: &Path, output: OutputMode) {
+    let origin = match flatzinc_differs {
+        Some(true) => {
+            "The FlatZinc differs too, so the mismatch likely comes from a flattening change"
+        }
+        Some(false) => "The FlatZinc is alike, so the mismatch likely comes from a solving change",
+        None => return,
+    };
+    match output {
+        OutputMode::Ascii => println!("[ORIGIN] {origin} (FlatZinc in {}).", dir.display()),
+        OutputMode::Rich => println!(
+            "\x1b[36m🔎 {origin}\x1b[0m (FlatZinc in \x1b[1m{}\x1b[0m).",
+            dir.display()
+        ),
+        OutputMode::None => {}
+    }
+}
@@ -0,0 +1,152 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+mod display;
+
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+use clap::Args;
+
+use crate::{
+    compiler::display::{report_compile_failure, report_origin},
+    diff::{
+        RunOptions, SolverSpec, check_solvers,
+        display::{
+            OutputMode, ReportOptions, print_diff, report_divergence, report_flatzinc,
+            report_minizinc,
+        },
+        flatzinc::{self, FznStats},
+        minizinc::compile,
+        parse_solver_spec, run_pair, single_solution_solvers,
+    },
+};
+
+#[derive(Args, Debug)]
+pub(crate) struct CompilerDiffArgs {
+    /// MiniZinc model file
+    model: PathBuf,
+    /// MiniZinc data file
+    instance: PathBuf,
+    /// Solver tag run by both installations, followed by --flags if needed
+    #[arg(value_parser = parse_solver_spec)]
+    solver: SolverSpec,
+    /// `minizinc` executable of the baseline installation (displayed on the left)
+    minizinc_left: PathBuf,
+    /// `minizinc` executable checked against the baseline (displayed on the right)
+    minizinc_right: PathBuf,
+    /// Timeout for both runs
+    #[arg(short, long)]
+    timeout_secs: Option<u64>,
+    /// Disables all output
+    #[arg(short, long)]
+    quiet: bool,
+    /// Solution checker model (.mzc); both solvers are stopped once it rejects a solution
+    #[arg(long, value_name = "FILE")]
+    checker: Option<PathBuf>,
+    /// Directory where the compiled FlatZinc is kept; by default a new temporary directory
+    #[arg(long, value_name = "DIR")]
+    fzn_dir: Option<PathBuf>,
+    /// Logs the command line, output, exit status and timestamps of every run to this directory
+    #[arg(long, value_name = "DIR")]
+    log_dir: Option<PathBuf>,
+    /// Prints the command of every run, with the MiniZinc environment variables, to stderr
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+/// Flattens the model for both solvers and compares the statistics of the FlatZinc.
+/// Returns whether the FlatZinc differs, if both compilations succeeded.
+pub(crate) fn compare_flatzinc(
+    model: &Path,
+    instance: &Path,
+    solvers: (&SolverSpec, &SolverSpec),
+    labels: (&str, &str),
+    dir: &Path,
+    verbose: bool,
+    output: OutputMode,
+) -> Option<bool> {
+    let mut stats = Vec::new();
+    for (label, solver) in [(labels.0, solvers.0), (labels.1, solvers.1)] {
+        let compiled = match compile(model, instance, solver, dir, verbose) {
+            Ok(compiled) => compiled,
+            Err(e) => {
+                report_compile_failure(label, &e, output);
+                return None;
+            }
+        };
+        match FznStats::read(&compiled.fzn) {
+            Ok(s) => stats.push(s),
+            Err(e) => {
+                eprintln!("Failed to read {}: {e}", compiled.fzn.display());
+                return None;
+            }
+        }
+    }
+    report_flatzinc((&stats[0], &stats[1]), labels, output);
+    Some(stats[0] != stats[1])
+}
+
+pub(crate) fn run(args: CompilerDiffArgs) -> ExitCode {
+    let output_mode = OutputMode::new(args.quiet);
+    let with_minizinc = |minizinc: &Path| SolverSpec {
+        minizinc: Some(minizinc.to_path_buf()),
+        ..args.solver.clone()
+    };
+    let (mut left, mut right) = (
+        with_minizinc(&args.minizinc_left),
+        with_minizinc(&args.minizinc_right),
+    );
+    if let Err(status) = check_solvers(&mut left, &mut right, output_mode) {
+        return status.into();
+    }
+    report_minizinc(&left, &right, output_mode);
+
+    let dir = args.fzn_dir.clone().unwrap_or_else(flatzinc::default_dir);
+    let flatzinc_differs = compare_flatzinc(
+        &args.model,
+        &args.instance,
+        (&left, &right),
+        ("Left", "Right"),
+        &dir,
+        args.verbose,
+        output_mode,
+    );
+
+    let options = RunOptions {
+        timeout: args.timeout_secs.map(Duration::from_secs),
+        checker: args.checker,
+        log_dir: args.log_dir,
+        verbose: args.verbose,
+        ..RunOptions::default()
+    };
+    let report_options = ReportOptions {
+        single_solution: single_solution_solvers(&left, &right),
+        ..ReportOptions::default()
+    };
+    let (res1, res2, divergence) =
+        run_pair(&args.model, &args.instance, left, right, &options, false);
+    let status = match divergence {
+        Some(divergence) => report_divergence(divergence, output_mode),
+        None => print_diff(&res1, &res2, output_mode, &report_options),
+    };
+    if status.verdict.is_some() {
+        report_origin(flatzinc_differs, &dir, output_mode);
+    }
+    status.into()
+}
@@ -0,0 +1,236 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{io, path::Path, process::Command};
+
+use serde_json::Value;
+
+/// Standard flags that change how a solver is run, and whether the solver supports them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Capabilities {
+    /// `-a`, printing all solutions
+    pub(crate) all_solutions: bool,
+    /// `-t`, a time limit
+    pub(crate) time_limit: bool,
+    /// `-r`, a random seed
+    pub(crate) random_seed: bool,
+    /// `-p`, parallel search
+    pub(crate) parallel: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities {
+            all_solutions: true,
+            time_limit: true,
+            random_seed: true,
+            parallel: true,
+        }
+    }
+}
+
+/// A solver known to MiniZinc, as listed by `minizinc --solvers-json`.
+#[derive(Debug, Clone)]
+pub(crate) struct SolverInfo {
+    /// Unique identifier, e.g. `org.gecode.gecode`
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) tags: Vec<String>,
+    /// Standard flags the solver supports, e.g. `-a` or `-p`
+    pub(crate) std_flags: Vec<String>,
+    /// Solver-specific flags, with their descriptions
+    pub(crate) extra_flags: Vec<(String, String)>,
+}
+
+impl SolverInfo {
+    fn from_json(value: &Value) -> Option<SolverInfo> {
+        let string = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+        let strings = |key: &str| -> Vec<String> {
+            value
+                .get(key)
+                .and_then(Value::as_array)
+                .map(|a| {
+                    a.iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        // Each extra flag is a list of its name, description, type and default value.
+        let extra_flags = value
+            .get("extraFlags")
+            .and_then(Value::as_array)
+            .map(|flags| {
+                flags
+                    .iter()
+                    .filter_map(|flag| {
+                        let flag = flag.as_array()?;
+                        let name = flag.first()?.as_str()?.to_string();
+                        let description = flag.get(1).and_then(Value::as_str).unwrap_or_default();
+                        Some((name, description.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(SolverInfo {
+            id: string("id")?,
+            name: string("name").unwrap_or_default(),
+            version: string("version").unwrap_or_default(),
+            tags: strings("tags"),
+            std_flags: strings("stdFlags"),
+            extra_flags,
+        })
+    }
+
+    /// Standard flags supported by the solver. Solvers that do not list any are assumed to
+    /// support them all, as was the case before MiniZinc checked them.
+    pub(crate) fn capabilities(&self) -> Capabilities {
+        if self.std_flags.is_empty() {
+            return Capabilities::default();
+        }
+        let supports = |flag: &str| self.std_flags.iter().any(|f| f == flag);
+        Capabilities {
+            all_solutions: supports("-a"),
+            time_limit: supports("-t"),
+            random_seed: supports("-r"),
+            parallel: supports("-p"),
+        }
+    }
+
+    /// Names that select the solver with `--solver`.
+    fn names(&self) -> impl Iterator<Item = &str> {
+        let last = self.id.rsplit('.').next();
+        std::iter::once(self.id.as_str())
+            .chain(last)
+            .chain(self.tags.iter().map(String::as_str))
+    }
+
+    /// Whether `--solver tag` selects this solver. MiniZinc accepts the identifier, its last
+    /// component and any of the tags, optionally followed by `@version`.
+    pub(crate) fn matches(&self, tag: &str) -> bool {
+        let (tag, version) = match tag.split_once('@') {
+            Some((tag, version)) => (tag, Some(version)),
+            None => (tag, None),
+        };
+        version.is_none_or(|version| version == self.version)
+            && self.names().any(|name| name.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Lists the solvers known to the `minizinc` executable.
+pub(crate) fn load(minizinc: &Path) -> io::Result<Vec<SolverInfo>> {
+    let output = Command::new(minizinc).arg("--solvers-json").output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+    let value: Value = serde_json::from_slice(&output.stdout).map_err(io::Error::other)?;
+    let solvers = value
+        .as_array()
+        .ok_or_else(|| io::Error::other("`--solvers-json` did not print a list"))?;
+    Ok(solvers.iter().filter_map(SolverInfo::from_json).collect())
+}
+
+/// Finds the solver selected by `tag`.
+pub(crate) fn find<'a>(solvers: &'a [SolverInfo], tag: &str) -> Option<&'a SolverInfo> {
+    solvers.iter().find(|s| s.matches(tag))
+}
+
+/// Number of single-character insertions, deletions and substitutions turning `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The name of a known solver closest to an unknown `tag`, if any is close enough to be a typo.
+pub(crate) fn suggest<'a>(solvers: &'a [SolverInfo], tag: &str) -> Option<&'a str> {
+    let tag = tag.to_lowercase();
+    let max_distance = (tag.chars().count() / 3).max(1);
+    solvers
+        .iter()
+        .flat_map(SolverInfo::names)
+        .map(|name| (edit_distance(&tag, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gecode() -> SolverInfo {
+        SolverInfo {
+            id: "org.gecode.gecode".to_string(),
+            name: "Gecode".to_string(),
+            version: "6.3.0".to_string(),
+            tags: vec!["cp".to_string(), "int".to_string()],
+            std_flags: vec!["-a".to_string(), "-t".to_string()],
+            extra_flags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("gecode", "gecode"), 0);
+        assert_eq!(edit_distance("gecod", "gecode"), 1);
+        assert_eq!(edit_distance("gceode", "gecode"), 2);
+        assert_eq!(edit_distance("", "cp"), 2);
+    }
+
+    #[test]
+    fn suggests_close_names_only() {
+        let solvers = [gecode()];
+        assert_eq!(suggest(&solvers, "Gecod"), Some("gecode"));
+        assert_eq!(suggest(&solvers, "chuffed"), None);
+    }
+
+    #[test]
+    fn matches_names_and_versions() {
+        let info = gecode();
+        assert!(info.matches("org.gecode.gecode"));
+        assert!(info.matches("Gecode"));
+        assert!(info.matches("cp"));
+        assert!(info.matches("gecode@6.3.0"));
+        assert!(!info.matches("gecode@6.2.0"));
+        assert!(!info.matches("chuffed"));
+    }
+
+    #[test]
+    fn capabilities_follow_the_standard_flags() {
+        let capabilities = gecode().capabilities();
+        assert!(capabilities.all_solutions && capabilities.time_limit);
+        assert!(!capabilities.random_seed && !capabilities.parallel);
+        let unlisted = SolverInfo {
+            std_flags: Vec::new(),
+            ..gecode()
+        };
+        assert_eq!(unlisted.capabilities(), Capabilities::default());
+    }
+}
@@ -13,69 +13,430 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
-use std::{process::ExitCode, time::Duration};
+use std::{
+    fs::File,
+    io::{BufWriter, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
 
-use crate::{diff::SolverOutput, hhmmss::Hhmmss};
+use crate::{
+    diff::{
+        CrashCategory, FailureKind, Side, SolverError, SolverOutput, SolverRun, SolverSpec,
+        flatzinc::{Compiled, FznStats},
+        minizinc::minizinc_version,
+        monitor::Divergence,
+        solution::{Deviation, Edit, align, deviations, pair_solutions},
+    },
+    hhmmss::Hhmmss,
+};
 
+#[derive(Clone, Copy)]
 pub(crate) enum OutputMode {
     Ascii,
     Rich,
     None,
 }
 
+impl OutputMode {
+    /// Picks colourful output when writing to a terminal and plain output otherwise,
+    /// like `grep` does.
+    pub(crate) fn new(quiet: bool) -> Self {
+        if quiet {
+            OutputMode::None
+        } else if std::io::stdout().is_terminal() {
+            OutputMode::Rich
+        } else {
+            OutputMode::Ascii
+        }
+    }
+}
+
+/// Renders a removed solution and its closest added solution as a single line, wrapping the
+/// differing values in the given markers, like `git diff --word-diff` does.
+pub(crate) fn word_diff(
+    removed: &str,
+    added: &str,
+    (removed_start, removed_end): (&str, &str),
+    (added_start, added_end): (&str, &str),
+) -> String {
+    let mut line = String::new();
+    let mut removed_run = String::new();
+    let mut added_run = String::new();
+    let flush = |line: &mut String, removed_run: &mut String, added_run: &mut String| {
+        if !removed_run.is_empty() {
+            line.push_str(&format!("{removed_start}{removed_run}{removed_end}"));
+            removed_run.clear();
+        }
+        if !added_run.is_empty() {
+            line.push_str(&format!("{added_start}{added_run}{added_end}"));
+            added_run.clear();
+        }
+    };
+    for edit in align(removed, added) {
+        match edit {
+            Edit::Same(s) => {
+                flush(&mut line, &mut removed_run, &mut added_run);
+                line.push_str(s);
+            }
+            Edit::Removed(s) => removed_run.push_str(s),
+            Edit::Added(s) => added_run.push_str(s),
+        }
+    }
+    flush(&mut line, &mut removed_run, &mut added_run);
+    line
+}
+
+/// Statistics and resource usage as (name, value) rows, where times are given in seconds
+/// and memory in bytes.
+fn stat_rows(run: &SolverRun) -> [(&'static str, Option<f64>); 9] {
+    let stats = &run.stats;
+    let count = |c: Option<u64>| c.map(|c| c as f64);
+    let seconds = |d: Option<Duration>| d.map(|d| d.as_secs_f64());
+    [
+        ("nodes", count(stats.nodes)),
+        ("failures", count(stats.failures)),
+        ("propagations", count(stats.propagations)),
+        ("restarts", count(stats.restarts)),
+        ("flatTime", seconds(stats.flat_time)),
+        ("solveTime", seconds(stats.solve_time)),
+        ("userTime", seconds(Some(run.usage.user_time))),
+        ("systemTime", seconds(Some(run.usage.system_time))),
+        ("maxRSS", count(Some(run.usage.max_rss))),
+    ]
+}
+
+fn format_stat(name: &str, value: Option<f64>) -> String {
+    match value {
+        None => "-".to_string(),
+        Some(v) if name.ends_with("Time") => format!("{v:.3}s"),
+        Some(v) if name == "maxRSS" => format!("{:.1}MiB", v / (1024.0 * 1024.0)),
+        Some(v) => format!("{v:.0}"),
+    }
+}
+
+/// Whether two values of a statistic differ by at least a factor of two. Times below a tenth
+/// of a second are too noisy to be compared.
+fn stats_diverge(name: &str, left: Option<f64>, right: Option<f64>) -> bool {
+    match (left, right) {
+        (Some(l), Some(r)) if name.ends_with("Time") && l.max(r) < 0.1 => false,
+        (Some(l), Some(r)) => l.max(r) >= 2.0 * l.min(r) && l.max(r) > 0.0,
+        _ => false,
+    }
+}
+
+fn print_stats_ascii(left: &SolverRun, right: &SolverRun) {
+    let rows: Vec<_> = stat_rows(left)
+        .into_iter()
+        .zip(stat_rows(right))
+        .filter(|((_, l), (_, r))| l.is_some() || r.is_some())
+        .collect();
+    if rows.is_empty() {
+        return;
+    }
+    println!("{:<14} {:>14} {:>14}", "Statistics", "Left", "Right");
+    for ((name, l), (_, r)) in rows {
+        println!(
+            "{:<14} {:>14} {:>14}{}",
+            name,
+            format_stat(name, l),
+            format_stat(name, r),
+            if stats_diverge(name, l, r) {
+                " (!)"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+fn print_stats_rich(left: &SolverRun, right: &SolverRun) {
+    let rows: Vec<_> = stat_rows(left)
+        .into_iter()
+        .zip(stat_rows(right))
+        .filter(|((_, l), (_, r))| l.is_some() || r.is_some())
+        .collect();
+    if rows.is_empty() {
+        return;
+    }
+    println!(
+        "📈 \x1b[1m{:<14}\x1b[0m \x1b[1m{:>14}\x1b[0m \x1b[1m{:>14}\x1b[0m",
+        "Statistics", "Left", "Right"
+    );
+    for ((name, l), (_, r)) in rows {
+        let color = if stats_diverge(name, l, r) {
+            "\x1b[33m"
+        } else {
+            ""
+        };
+        println!(
+            "   {color}{:<14} {:>14} {:>14}\x1b[0m",
+            name,
+            format_stat(name, l),
+            format_stat(name, r),
+        );
+    }
+}
+
+/// Splits the duration of a run into flattening and solving, if MiniZinc reported them.
+fn timing_breakdown(run: &SolverRun, format: impl Fn(Duration) -> String) -> String {
+    let parts: Vec<String> = [
+        ("flattening", run.flattening_time()),
+        ("solving", run.solving_time()),
+        ("first solution", run.first_solution),
+    ]
+    .into_iter()
+    .filter_map(|(name, d)| d.map(|d| format!("{name}: {}", format(d))))
+    .collect();
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
+
 fn print_diff_ascii(
-    added: &[&String],
-    removed: &[&String],
+    added: &[String],
+    removed: &[String],
     n_shared: usize,
-    dur_left: Duration,
-    dur_right: Duration,
+    left: &SolverRun,
+    right: &SolverRun,
 ) {
     if added.is_empty() && removed.is_empty() {
         println!("[OK] All {n_shared} solutions match.");
-        println!("Left time: {} \x1b[0m", dur_left.as_millis());
-        println!("Right time: {} \x1b[0m", dur_right.as_millis());
+        let millis = |d: Duration| d.as_millis().to_string();
+        println!(
+            "Left time: {}{} \x1b[0m",
+            left.duration.as_millis(),
+            timing_breakdown(left, millis)
+        );
+        println!(
+            "Right time: {}{} \x1b[0m",
+            right.duration.as_millis(),
+            timing_breakdown(right, millis)
+        );
+        print_stats_ascii(left, right);
         return;
     }
 
-    println!("[FAIL] Mismatch found:");
-    for sol in removed {
+    let verdict = Verdict::classify(removed, added).expect("sets differ");
+    println!("[FAIL] Mismatch found ({}):", verdict.describe());
+    let pairing = pair_solutions(removed, added);
+    for (sol_removed, sol_added) in pairing.pairs {
+        println!(
+            "~ {}",
+            word_diff(sol_removed, sol_added, ("[-", "-]"), ("{+", "+}"))
+        );
+    }
+    for sol in pairing.removed {
         println!("- {}", sol);
     }
-    for sol in added {
+    for sol in pairing.added {
         println!("+ {}", sol);
     }
+    print_stats_ascii(left, right);
 }
 
 fn print_diff_rich(
-    added: &[&String],
-    removed: &[&String],
+    added: &[String],
+    removed: &[String],
     n_shared: usize,
-    dur_left: Duration,
-    dur_right: Duration,
+    left: &SolverRun,
+    right: &SolverRun,
 ) {
     if added.is_empty() && removed.is_empty() {
         println!("\x1b[32m✅ All {n_shared} solutions \x1b[1mmatch\x1b[0m.\x1b[0m");
         println!(
-            "\x1b[32m⌛ Duration of left run: {} \x1b[0m",
-            dur_left.hhmmssxxx()
+            "\x1b[32m⌛ Duration of left run: {}{} \x1b[0m",
+            left.duration.hhmmssxxx(),
+            timing_breakdown(left, |d| d.hhmmssxxx())
         );
         println!(
-            "\x1b[32m⌛ Duration of right run: {} \x1b[0m",
-            dur_right.hhmmssxxx()
+            "\x1b[32m⌛ Duration of right run: {}{} \x1b[0m",
+            right.duration.hhmmssxxx(),
+            timing_breakdown(right, |d| d.hhmmssxxx())
         );
+        print_stats_rich(left, right);
         return;
     }
 
-    println!("\x1b[31m❌ Mismatch found:\x1b[0m");
+    let verdict = Verdict::classify(removed, added).expect("sets differ");
+    println!(
+        "\x1b[31m❌ Mismatch found (\x1b[1m{}\x1b[0m\x1b[31m):\x1b[0m",
+        verdict.describe()
+    );
 
-    for sol in removed {
+    let pairing = pair_solutions(removed, added);
+    for (sol_removed, sol_added) in pairing.pairs {
+        println!(
+            "\x1b[33m~\x1b[0m {}",
+            word_diff(
+                sol_removed,
+                sol_added,
+                ("\x1b[1;31m", "\x1b[0m"),
+                ("\x1b[1;32m", "\x1b[0m"),
+            )
+        );
+    }
+    for sol in pairing.removed {
         println!("\x1b[31m- {}\x1b[0m", sol);
     }
-    for sol in added {
+    for sol in pairing.added {
         println!("\x1b[32m+ {}\x1b[0m", sol);
     }
+    print_stats_rich(left, right);
+}
+
+/// Condensed view of a large mismatch: counts, a few example solutions from each side, and the
+/// variable values that are most typical of the solutions found by only one solver.
+struct Summary<'a> {
+    verdict: Verdict,
+    removed: Vec<String>,
+    added: Vec<String>,
+    n_shared: usize,
+    removed_deviations: Vec<Deviation>,
+    added_deviations: Vec<Deviation>,
+    examples: usize,
+    diff_file: Option<&'a Path>,
+    runs: (&'a SolverRun, &'a SolverRun),
 }
 
+fn print_summary_ascii(summary: &Summary) {
+    let k = summary.examples;
+    println!(
+        "[FAIL] Mismatch found ({}): {} solutions only in left, {} only in right, {} shared.",
+        summary.verdict.describe(),
+        summary.removed.len(),
+        summary.added.len(),
+        summary.n_shared
+    );
+    for (side, sols, marker) in [
+        ("left", &summary.removed, '-'),
+        ("right", &summary.added, '+'),
+    ] {
+        if sols.is_empty() {
+            continue;
+        }
+        println!(
+            "Solutions only in {side} (showing {} of {}):",
+            sols.len().min(k),
+            sols.len()
+        );
+        for sol in sols.iter().take(k) {
+            println!("{marker} {sol}");
+        }
+    }
+    for (side, other, deviations) in [
+        ("left", "right", &summary.removed_deviations),
+        ("right", "left", &summary.added_deviations),
+    ] {
+        if deviations.is_empty() {
+            continue;
+        }
+        println!("Values most typical of solutions only in {side}:");
+        for d in deviations.iter().take(k) {
+            println!(
+                "  {} = {} in {:.1}% of them vs. {:.1}% of {other} solutions",
+                d.variable,
+                d.value,
+                100.0 * d.share,
+                100.0 * d.reference_share
+            );
+        }
+    }
+    if let Some(path) = summary.diff_file {
+        println!("Full lists written to {}", path.display());
+    }
+    print_stats_ascii(summary.runs.0, summary.runs.1);
+}
+
+fn print_summary_rich(summary: &Summary) {
+    let k = summary.examples;
+    println!(
+        "\x1b[31m❌ Mismatch found (\x1b[1m{}\x1b[0m\x1b[31m): \x1b[1m{}\x1b[0m\x1b[31m solutions only in left, \x1b[1m{}\x1b[0m\x1b[31m only in right, {} shared.\x1b[0m",
+        summary.verdict.describe(),
+        summary.removed.len(),
+        summary.added.len(),
+        summary.n_shared
+    );
+    for (side, sols, color, marker) in [
+        ("left", &summary.removed, 31, '-'),
+        ("right", &summary.added, 32, '+'),
+    ] {
+        if sols.is_empty() {
+            continue;
+        }
+        println!(
+            "📋 Solutions only in \x1b[1m{side}\x1b[0m (showing {} of {}):",
+            sols.len().min(k),
+            sols.len()
+        );
+        for sol in sols.iter().take(k) {
+            println!("\x1b[{color}m{marker} {sol}\x1b[0m");
+        }
+    }
+    for (side, other, deviations) in [
+        ("left", "right", &summary.removed_deviations),
+        ("right", "left", &summary.added_deviations),
+    ] {
+        if deviations.is_empty() {
+            continue;
+        }
+        println!("📊 Values most typical of solutions only in \x1b[1m{side}\x1b[0m:");
+        for d in deviations.iter().take(k) {
+            println!(
+                "   \x1b[1m{}\x1b[0m = {} in {:.1}% of them vs. {:.1}% of {other} solutions",
+                d.variable,
+                d.value,
+                100.0 * d.share,
+                100.0 * d.reference_share
+            );
+        }
+    }
+    if let Some(path) = summary.diff_file {
+        println!("📄 Full lists written to \x1b[1m{}\x1b[0m", path.display());
+    }
+    print_stats_rich(summary.runs.0, summary.runs.1);
+}
+
+fn write_diff_file(path: &Path, removed: &[String], added: &[String]) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    writeln!(file, "# Solutions only in left ({})", removed.len())?;
+    for sol in removed {
+        writeln!(file, "- {sol}")?;
+    }
+    writeln!(file, "# Solutions only in right ({})", added.len())?;
+    for sol in added {
+        writeln!(file, "+ {sol}")?;
+    }
+    file.flush()
+}
+
+/// Options controlling how solution mismatches are reported.
+#[derive(Default)]
+pub(crate) struct ReportOptions {
+    /// Summarise mismatches with at most this many examples per side.
+    pub(crate) summary: Option<usize>,
+    /// Where to write the full lists of differing solutions.
+    pub(crate) diff_file: Option<PathBuf>,
+    /// Solvers that cannot list all solutions and stop at their first one, if any.
+    pub(crate) single_solution: Option<SolverErrorType>,
+}
+
+impl ReportOptions {
+    /// Whether the solver on `side` lists all solutions when its search finishes.
+    fn lists_all(&self, side: Side) -> bool {
+        match (self.single_solution, side) {
+            (None, _) => true,
+            (Some(SolverErrorType::Left), side) => side == Side::Right,
+            (Some(SolverErrorType::Right), side) => side == Side::Left,
+            (Some(SolverErrorType::Both), _) => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 pub(crate) enum SolverErrorType {
     Left,
     Right,
@@ -100,7 +461,7 @@ fn report_timeout_rich(result: SolverErrorType) {
     println!("\x1b[33m⏳ \x1b[1m{}\x1b[0m timed out\x1b[0m", result_str);
 }
 
-fn report_timeout(result: SolverErrorType, output: OutputMode) {
+pub(crate) fn report_timeout(result: SolverErrorType, output: OutputMode) {
     match output {
         OutputMode::Ascii => report_timeout_ascii(result),
         OutputMode::Rich => report_timeout_rich(result),
@@ -108,37 +469,392 @@ fn report_timeout(result: SolverErrorType, output: OutputMode) {
     };
 }
 
-pub(crate) enum CheckStatus {
-    Success,
-    Diff,
-    CrashLeft,
-    CrashRight,
-    CrashBoth,
-    TimeoutLeft,
-    TimeoutRight,
-    TimeoutBoth,
+/// How the solution sets of the two solvers relate when they differ. Missed solutions
+/// usually point to wrong propagation that prunes too much, extra solutions to incomplete
+/// propagation that fails to rule them out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Verdict {
+    /// The left solver found a strict subset of the solutions of the right one.
+    Subset,
+    /// The left solver found a strict superset of the solutions of the right one.
+    Superset,
+    /// Each solver found solutions that the other one did not.
+    Incomparable,
+    /// The given solver, whose search did not finish, found solutions missing from the
+    /// complete search of the other one. How the whole solution sets relate is unknown.
+    Outside(Side),
+    /// The solution checker rejected a solution of the given solver. How the whole solution
+    /// sets relate is unknown.
+    Rejected(Side),
+}
+
+impl Verdict {
+    /// Classifies the difference between the solutions only found by the left solver
+    /// (`removed`) and those only found by the right one (`added`).
+    pub(crate) fn classify(removed: &[String], added: &[String]) -> Option<Verdict> {
+        match (removed.is_empty(), added.is_empty()) {
+            (true, true) => None,
+            (true, false) => Some(Verdict::Subset),
+            (false, true) => Some(Verdict::Superset),
+            (false, false) => Some(Verdict::Incomparable),
+        }
+    }
+
+    /// Verdict implied by a solution found by one solver only.
+    pub(crate) fn extra_on(side: Side) -> Verdict {
+        match side {
+            Side::Left => Verdict::Superset,
+            Side::Right => Verdict::Subset,
+        }
+    }
+
+    /// Short name of the verdict, e.g. `subset`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Verdict::Subset => "subset",
+            Verdict::Superset => "superset",
+            Verdict::Incomparable => "incomparable",
+            Verdict::Outside(Side::Left) => "outside-left",
+            Verdict::Outside(Side::Right) => "outside-right",
+            Verdict::Rejected(Side::Left) => "rejected-left",
+            Verdict::Rejected(Side::Right) => "rejected-right",
+        }
+    }
+
+    pub(crate) fn describe(self) -> &'static str {
+        match self {
+            Verdict::Subset => "subset, left solver missed solutions",
+            Verdict::Superset => "superset, left solver found extra solutions",
+            Verdict::Incomparable => "incomparable, both solvers found solutions the other did not",
+            Verdict::Outside(Side::Left) => {
+                "outside, left solver found solutions outside the complete search of right solver"
+            }
+            Verdict::Outside(Side::Right) => {
+                "outside, right solver found solutions outside the complete search of left solver"
+            }
+            Verdict::Rejected(Side::Left) => {
+                "rejected, the checker rejected a solution of left solver"
+            }
+            Verdict::Rejected(Side::Right) => {
+                "rejected, the checker rejected a solution of right solver"
+            }
+        }
+    }
+}
+
+/// What became of the run of one solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    Complete,
+    Failed(FailureKind),
+    /// Significantly slower than the baseline, as found by `perf`
+    Slowdown,
+    /// Stopped along with the other run at the first difference found while both ran
+    Stopped,
+}
+
+impl Outcome {
+    pub(crate) fn describe(self) -> &'static str {
+        match self {
+            Outcome::Complete => "completed",
+            Outcome::Failed(FailureKind::Crash) => "crash",
+            Outcome::Failed(FailureKind::Timeout) => "timeout",
+            Outcome::Failed(FailureKind::Memout) => "memout",
+            Outcome::Failed(FailureKind::CpuOut) => "CPU time limit",
+            Outcome::Failed(FailureKind::OutputOverflow) => "output overflow",
+            Outcome::Slowdown => "significant slowdown",
+            Outcome::Stopped => "stopped at the first difference",
+        }
+    }
+
+    fn bits(self) -> u8 {
+        match self {
+            Outcome::Complete => 0,
+            Outcome::Failed(FailureKind::Crash) => 1,
+            Outcome::Failed(FailureKind::Timeout) => 2,
+            Outcome::Failed(FailureKind::Memout) => 3,
+            Outcome::Failed(FailureKind::CpuOut) => 4,
+            Outcome::Failed(FailureKind::OutputOverflow) => 5,
+            Outcome::Slowdown => 6,
+            Outcome::Stopped => 7,
+        }
+    }
+}
+
+/// The result of a check: the outcome of each run, along with how the solutions differ,
+/// if they have been found to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CheckStatus {
+    pub(crate) left: Outcome,
+    pub(crate) right: Outcome,
+    pub(crate) verdict: Option<Verdict>,
+}
+
+impl CheckStatus {
+    pub(crate) const SUCCESS: CheckStatus = CheckStatus {
+        left: Outcome::Complete,
+        right: Outcome::Complete,
+        verdict: None,
+    };
+
+    pub(crate) fn failure(kind: FailureKind, solvers: SolverErrorType) -> CheckStatus {
+        let failed = Outcome::Failed(kind);
+        match solvers {
+            SolverErrorType::Left => CheckStatus {
+                left: failed,
+                ..CheckStatus::SUCCESS
+            },
+            SolverErrorType::Right => CheckStatus {
+                right: failed,
+                ..CheckStatus::SUCCESS
+            },
+            SolverErrorType::Both => CheckStatus {
+                left: failed,
+                right: failed,
+                verdict: None,
+            },
+        }
+    }
+
+    pub(crate) fn diff(verdict: Verdict) -> CheckStatus {
+        CheckStatus {
+            verdict: Some(verdict),
+            ..CheckStatus::SUCCESS
+        }
+    }
+
+    /// The exit code of the check.
+    pub(crate) fn code(self) -> u8 {
+        // Success corresponds to the zero exit code, as per normal. Otherwise:
+        // - the three least significant bits encode the outcome of the left run:
+        //   0 if it completed, 1 for a crash, 2 for a timeout, 3 for a memout,
+        //   4 for a CPU time limit, 5 for an output overflow, 6 for a significant
+        //   slowdown found by `perf`, and 7 if it was stopped at the first difference
+        //   found while both solvers ran, in which case the verdict is unknown,
+        // - the next three bits encode the outcome of the right run in the same way,
+        // - and the two most significant bits encode the verdict on the solutions:
+        //   0 if no difference was found, 1 if the left solver missed solutions (subset),
+        //   2 if it found extra ones (superset), and 3 if both happened (incomparable).
+        //   A run that did not finish can only be shown to have found solutions missing
+        //   from the other one, which is encoded as 1 for the right run and 2 for the left
+        //   one; its own outcome tells this apart from a subset or superset. A solution
+        //   rejected by the checker is encoded the same way, with both runs stopped.
+        let verdict_bits = match self.verdict {
+            None => 0,
+            Some(
+                Verdict::Subset | Verdict::Outside(Side::Right) | Verdict::Rejected(Side::Right),
+            ) => 1,
+            Some(
+                Verdict::Superset | Verdict::Outside(Side::Left) | Verdict::Rejected(Side::Left),
+            ) => 2,
+            Some(Verdict::Incomparable) => 3,
+        };
+        self.left.bits() | (self.right.bits() << 3) | (verdict_bits << 6)
+    }
 }
 
 impl From<CheckStatus> for ExitCode {
     fn from(value: CheckStatus) -> Self {
-        let code = match value {
-            // Success correspond to the zero exit code, as per normal.
-            CheckStatus::Success => 0,
-            // Diff corresponds to setting all bits in the exit code.
-            CheckStatus::Diff => u8::MAX,
-            // If status is neither success nor difference, then:
-            // - the third least significant bit is set to 1 for a timeout and to 0 for a crash,
-            // - the second least significant bits is set to 1 if the event (timeout/crash)
-            //   has happened to the right solver,
-            // - and the least significant bit is set similarly for the left solver.
-            CheckStatus::CrashLeft => 1,
-            CheckStatus::CrashRight => 2,
-            CheckStatus::CrashBoth => 3,
-            CheckStatus::TimeoutLeft => 5,
-            CheckStatus::TimeoutRight => 6,
-            CheckStatus::TimeoutBoth => 7,
+        ExitCode::from(value.code())
+    }
+}
+
+fn print_partial_extras_ascii(extras: &[String], limit: usize, n_partial: usize, side: Side) {
+    let (marker, other) = match side {
+        Side::Left => ('-', "right"),
+        Side::Right => ('+', "left"),
+    };
+    println!(
+        "[FAIL] {} of {n_partial} solutions of {} solver, which did not finish, are missing from the complete search of {other} solver:",
+        extras.len(),
+        side_str(side)
+    );
+    for sol in extras.iter().take(limit) {
+        println!("{marker} {sol}");
+    }
+}
+
+fn print_partial_extras_rich(extras: &[String], limit: usize, n_partial: usize, side: Side) {
+    let (marker, color, other) = match side {
+        Side::Left => ('-', 31, "right"),
+        Side::Right => ('+', 32, "left"),
+    };
+    println!(
+        "\x1b[31m❌ \x1b[1m{}\x1b[0m\x1b[31m of {n_partial} solutions of {} solver, which did not finish, are missing from the complete search of {other} solver:\x1b[0m",
+        extras.len(),
+        side_str(side)
+    );
+    for sol in extras.iter().take(limit) {
+        println!("\x1b[{color}m{marker} {sol}\x1b[0m");
+    }
+}
+
+/// Checks the solutions found by a run that did not finish against the complete search of
+/// the other solver. Any solution missing from the complete set is a proven bug, even though
+/// the search of the unfinished run is incomplete, so that the sets cannot be classified.
+fn check_partial(
+    partial: &SolverRun,
+    complete: &SolverRun,
+    side: Side,
+    output: OutputMode,
+    options: &ReportOptions,
+) -> Option<Verdict> {
+    let mut extras = partial.solutions.difference(&complete.solutions);
+    if extras.is_empty() {
+        if partial.solutions.len() == 0 {
+            return None;
+        }
+        match output {
+            OutputMode::Ascii => println!(
+                "[OK] All {} solutions found by {} solver before it stopped are consistent",
+                partial.solutions.len(),
+                side_str(side)
+            ),
+            OutputMode::Rich => println!(
+                "\x1b[32m✅ All \x1b[1m{}\x1b[0m\x1b[32m solutions found by {} solver before it stopped are consistent\x1b[0m",
+                partial.solutions.len(),
+                side_str(side)
+            ),
+            OutputMode::None => {}
+        }
+        return None;
+    }
+
+    extras.sort();
+    if let Some(path) = &options.diff_file {
+        let result = match side {
+            Side::Left => write_diff_file(path, &extras, &[]),
+            Side::Right => write_diff_file(path, &[], &extras),
         };
-        ExitCode::from(code)
+        if let Err(e) = result {
+            eprintln!("Failed to write {}: {e}", path.display());
+        }
+    }
+    let limit = options.summary.unwrap_or(usize::MAX);
+    match output {
+        OutputMode::Ascii => {
+            print_partial_extras_ascii(&extras, limit, partial.solutions.len(), side)
+        }
+        OutputMode::Rich => {
+            print_partial_extras_rich(&extras, limit, partial.solutions.len(), side)
+        }
+        OutputMode::None => {}
+    }
+    Some(Verdict::Outside(side))
+}
+
+/// Checks the solution found by a solver that stops at its first one against the complete
+/// search of the other solver. A solution missing from the complete set is a proven bug, but
+/// how the whole sets relate is unknown.
+fn check_first_solution(
+    reduced: &SolverRun,
+    complete: &SolverRun,
+    side: Side,
+    output: OutputMode,
+) -> Option<Verdict> {
+    let (name, other, marker, color) = match side {
+        Side::Left => ("Left", "right", '-', 31),
+        Side::Right => ("Right", "left", '+', 32),
+    };
+    let Some(solution) = reduced.solutions.difference(&complete.solutions).pop() else {
+        let message = format!(
+            "The solution found by {} solver, which stops at its first one, is among those of {other} solver.",
+            side_str(side)
+        );
+        match output {
+            OutputMode::Ascii => println!("[OK] {message}"),
+            OutputMode::Rich => println!("\x1b[32m✅ {message}\x1b[0m"),
+            OutputMode::None => {}
+        }
+        return None;
+    };
+    let verdict = Verdict::Outside(side);
+    match output {
+        OutputMode::Ascii => {
+            println!(
+                "[FAIL] {name} solver, which stops at its first solution, found one missing from the complete search of {other} solver:"
+            );
+            println!("{marker} {solution}");
+        }
+        OutputMode::Rich => {
+            println!(
+                "\x1b[31m❌ {name} solver, which stops at its first solution, found one missing from the complete search of {other} solver:\x1b[0m"
+            );
+            println!("\x1b[{color}m{marker} {solution}\x1b[0m");
+        }
+        OutputMode::None => {}
+    }
+    Some(verdict)
+}
+
+/// Checks the runs when `reduced` solvers stop at their first solution. A solution found by
+/// one solver while the other proves there is none is a mismatch. When only one solver stops
+/// early, its solution must also be among those of the other one; when both do, they may
+/// find different ones, which are then not compared.
+fn check_single_solution(
+    left: &SolverRun,
+    right: &SolverRun,
+    reduced: SolverErrorType,
+    output: OutputMode,
+) -> Option<Verdict> {
+    let (found_left, found_right) = (left.solutions.len() > 0, right.solutions.len() > 0);
+    let (side, solution) = match (found_left, found_right, reduced) {
+        (true, false, _) => (Side::Left, left.solutions.texts().next()),
+        (false, true, _) => (Side::Right, right.solutions.texts().next()),
+        (true, true, SolverErrorType::Left) => {
+            return check_first_solution(left, right, Side::Left, output);
+        }
+        (true, true, SolverErrorType::Right) => {
+            return check_first_solution(right, left, Side::Right, output);
+        }
+        (found, _, _) => {
+            let message = if found {
+                "Both solvers found a solution; the solutions are not compared, as both solvers stop at their first one."
+            } else {
+                "Neither solver found a solution."
+            };
+            match output {
+                OutputMode::Ascii => println!("[OK] {message}"),
+                OutputMode::Rich => println!("\x1b[32m✅ {message}\x1b[0m"),
+                OutputMode::None => {}
+            }
+            return None;
+        }
+    };
+    let verdict = Verdict::extra_on(side);
+    let (side, other, marker, color) = match side {
+        Side::Left => ("Left", "right", '-', 31),
+        Side::Right => ("Right", "left", '+', 32),
+    };
+    let solution = solution.unwrap_or_default();
+    match output {
+        OutputMode::Ascii => {
+            println!(
+                "[FAIL] {side} solver found a solution, but {other} solver found none ({}):",
+                verdict.describe()
+            );
+            println!("{marker} {solution}");
+        }
+        OutputMode::Rich => {
+            println!(
+                "\x1b[31m❌ {side} solver found a solution, but {other} solver found none ({}):\x1b[0m",
+                verdict.describe()
+            );
+            println!("\x1b[{color}m{marker} {solution}\x1b[0m");
+        }
+        OutputMode::None => {}
+    }
+    Some(verdict)
+}
+
+/// Reports why a run did not finish.
+fn report_unfinished(run: &SolverOutput, solvers: SolverErrorType, output: OutputMode) {
+    match run {
+        SolverOutput::Complete(_) => {}
+        SolverOutput::Timeout(_) => report_timeout(solvers, output),
+        SolverOutput::Failed(e, _) => {
+            report_failure(e.clone(), solvers, output);
+        }
     }
 }
 
@@ -146,75 +862,560 @@ pub(crate) fn print_diff(
     set_left: &SolverOutput,
     set_right: &SolverOutput,
     output: OutputMode,
+    options: &ReportOptions,
 ) -> CheckStatus {
+    let status = CheckStatus {
+        left: set_left.outcome(),
+        right: set_right.outcome(),
+        verdict: None,
+    };
+    // A rejected solution is a bug in itself, whatever the other run found.
+    let rejected = check_rejection(set_left.run(), Side::Left, output)
+        .or_else(|| check_rejection(set_right.run(), Side::Right, output));
+    if let Some(verdict) = rejected {
+        return CheckStatus {
+            verdict: Some(verdict),
+            ..status
+        };
+    }
     match (set_left, set_right) {
-        (SolverOutput::Timeout, SolverOutput::Timeout) => {
+        (SolverOutput::Timeout(_), SolverOutput::Timeout(_)) => {
             report_timeout(SolverErrorType::Both, output);
-            CheckStatus::TimeoutBoth
+            status
         }
-        (SolverOutput::Timeout, SolverOutput::Complete(_, _)) => {
-            report_timeout(SolverErrorType::Left, output);
-            CheckStatus::TimeoutLeft
+        (SolverOutput::Failed(e_left, _), SolverOutput::Failed(e_right, _))
+            if e_left.kind == e_right.kind =>
+        {
+            let details = |e: &SolverError| match e.describe() {
+                Some(description) => format!(" ({description})"),
+                None => String::new(),
+            };
+            let e = SolverError {
+                kind: e_left.kind,
+                message: format!(
+                    "\nLeft{}: {}\nRight{}: {}",
+                    details(e_left),
+                    e_left.message,
+                    details(e_right),
+                    e_right.message
+                ),
+                termination: None,
+                category: None,
+            };
+            report_failure(e, SolverErrorType::Both, output);
+            status
         }
-        (SolverOutput::Complete(_, _), SolverOutput::Timeout) => {
-            report_timeout(SolverErrorType::Right, output);
-            CheckStatus::TimeoutRight
+        (SolverOutput::Complete(left), SolverOutput::Complete(right))
+            if let Some(reduced) = options.single_solution =>
+        {
+            let verdict = check_single_solution(left, right, reduced, output);
+            CheckStatus { verdict, ..status }
         }
-        (
-            SolverOutput::Complete(set_left, dur_left),
-            SolverOutput::Complete(set_right, dur_right),
-        ) => {
-            let added: Vec<_> = set_right.difference(set_left).collect();
-            let removed: Vec<_> = set_left.difference(set_right).collect();
-            let num_shared = set_left.intersection(set_right).count();
+        (SolverOutput::Complete(left), SolverOutput::Complete(right)) => {
+            let (set_left, set_right) = (&left.solutions, &right.solutions);
+            let mut added = set_right.difference(set_left);
+            let mut removed = set_left.difference(set_right);
+            // Sets iterate in no particular order, so the solutions are sorted to be shown and
+            // paired the same way on every run.
+            added.sort();
+            removed.sort();
+            let num_shared = set_left.len() - removed.len();
+            let verdict = Verdict::classify(&removed, &added);
 
-            match output {
-                OutputMode::Ascii => print_diff_ascii(
+            if let Some(path) = &options.diff_file
+                && let Err(e) = write_diff_file(path, &removed, &added)
+            {
+                eprintln!("Failed to write {}: {e}", path.display());
+            }
+
+            match (output, options.summary.filter(|_| verdict.is_some())) {
+                (OutputMode::None, _) => {}
+                (OutputMode::Ascii, None) => print_diff_ascii(
                     added.as_slice(),
                     removed.as_slice(),
                     num_shared,
-                    *dur_left,
-                    *dur_right,
+                    left,
+                    right,
                 ),
-                OutputMode::Rich => print_diff_rich(
+                (OutputMode::Rich, None) => print_diff_rich(
                     added.as_slice(),
                     removed.as_slice(),
                     num_shared,
-                    *dur_left,
-                    *dur_right,
+                    left,
+                    right,
                 ),
-                OutputMode::None => {}
+                (output, Some(examples)) => {
+                    let summary = Summary {
+                        verdict: verdict.expect("sets differ"),
+                        removed_deviations: deviations(&removed, set_right.texts()),
+                        added_deviations: deviations(&added, set_left.texts()),
+                        removed,
+                        added,
+                        n_shared: num_shared,
+                        examples,
+                        diff_file: options.diff_file.as_deref(),
+                        runs: (left, right),
+                    };
+                    if matches!(output, OutputMode::Rich) {
+                        print_summary_rich(&summary);
+                    } else {
+                        print_summary_ascii(&summary);
+                    }
+                }
             };
 
-            if added.is_empty() && removed.is_empty() {
-                CheckStatus::Success
-            } else {
-                CheckStatus::Diff
-            }
+            CheckStatus { verdict, ..status }
+        }
+        // Partial runs cannot be checked against a single solution, so they are only reported.
+        (SolverOutput::Complete(complete), partial) if options.lists_all(Side::Left) => {
+            report_unfinished(partial, SolverErrorType::Right, output);
+            let verdict = check_partial(partial.run(), complete, Side::Right, output, options);
+            CheckStatus { verdict, ..status }
+        }
+        (partial, SolverOutput::Complete(complete)) if options.lists_all(Side::Right) => {
+            report_unfinished(partial, SolverErrorType::Left, output);
+            let verdict = check_partial(partial.run(), complete, Side::Left, output, options);
+            CheckStatus { verdict, ..status }
+        }
+        (left, right) => {
+            report_unfinished(left, SolverErrorType::Left, output);
+            report_unfinished(right, SolverErrorType::Right, output);
+            status
         }
     }
 }
 
-pub(crate) fn report_crash(e: String, solvers: SolverErrorType, output: OutputMode) -> CheckStatus {
+pub(crate) fn report_failure(
+    e: SolverError,
+    solvers: SolverErrorType,
+    output: OutputMode,
+) -> CheckStatus {
     let solvers_str = match solvers {
         SolverErrorType::Left => "left solver",
         SolverErrorType::Right => "right solver",
         SolverErrorType::Both => "both solvers",
     };
-    match output {
-        OutputMode::Ascii => {
-            println!("[FAIL] Error running {solvers_str}: {e}");
+    let solvers_str = match e.describe() {
+        Some(description) => format!("{solvers_str} ({description})"),
+        None => solvers_str.to_string(),
+    };
+    let message = e.message;
+    let is_model_error = e.category.is_some_and(CrashCategory::is_model_error);
+    match (output, e.kind) {
+        (OutputMode::Ascii, FailureKind::Crash) if is_model_error => {
+            println!("[MODEL-ERROR] Model rejected by {solvers_str}: {message}");
         }
-        OutputMode::Rich => {
+        (OutputMode::Ascii, FailureKind::Crash | FailureKind::Timeout) => {
+            println!("[FAIL] Error running {solvers_str}: {message}");
+        }
+        (OutputMode::Ascii, FailureKind::Memout) => {
+            println!("[MEMOUT] Memory limit exceeded by {solvers_str}: {message}");
+        }
+        (OutputMode::Ascii, FailureKind::CpuOut) => {
+            println!("[CPU-OUT] CPU time limit exceeded by {solvers_str}: {message}");
+        }
+        (OutputMode::Ascii, FailureKind::OutputOverflow) => {
+            println!("[OUTPUT-OVERFLOW] Output limit exceeded by {solvers_str}: {message}");
+        }
+        (OutputMode::Rich, FailureKind::Crash) if is_model_error => {
+            println!("\x1b[33m📝 Model rejected by \x1b[1m{solvers_str}\x1b[0m:\x1b[0m");
+            println!("{}", message);
+        }
+        (OutputMode::Rich, FailureKind::Crash | FailureKind::Timeout) => {
             println!("\x1b[31m⛔ Error running \x1b[1m{solvers_str}\x1b[0m:\x1b[0m",);
-            println!("{}", e);
+            println!("{}", message);
+        }
+        (OutputMode::Rich, FailureKind::Memout) => {
+            println!("\x1b[33m🧠 Memory limit exceeded by \x1b[1m{solvers_str}\x1b[0m:\x1b[0m");
+            println!("{}", message);
         }
+        (OutputMode::Rich, FailureKind::CpuOut) => {
+            println!("\x1b[33m🔥 CPU time limit exceeded by \x1b[1m{solvers_str}\x1b[0m:\x1b[0m");
+            println!("{}", message);
+        }
+        (OutputMode::Rich, FailureKind::OutputOverflow) => {
+            println!("\x1b[33m📜 Output limit exceeded by \x1b[1m{solvers_str}\x1b[0m:\x1b[0m");
+            println!("{}", message);
+        }
+        (OutputMode::None, _) => {}
+    };
+
+    CheckStatus::failure(e.kind, solvers)
+}
+
+fn side_str(side: Side) -> &'static str {
+    match side {
+        Side::Left => "left",
+        Side::Right => "right",
+    }
+}
+
+fn report_divergence_ascii(divergence: &Divergence) {
+    match divergence {
+        Divergence::Mismatch { index, left, right } => {
+            println!(
+                "[FAIL] Solution #{} differs, so the solvers do not find the same solutions in the same order:",
+                index + 1
+            );
+            println!("~ {}", word_diff(left, right, ("[-", "-]"), ("{+", "+}")));
+        }
+        Divergence::Extra {
+            index,
+            side,
+            solution,
+        } => {
+            let (marker, other) = match side {
+                Side::Left => ('-', "right"),
+                Side::Right => ('+', "left"),
+            };
+            println!(
+                "[FAIL] Solution #{} of {} solver is missing from the complete search of {other} solver ({}):",
+                index + 1,
+                side_str(*side),
+                Verdict::extra_on(*side).describe()
+            );
+            println!("{marker} {solution}");
+        }
+        Divergence::Rejected {
+            side,
+            solution,
+            report,
+        } => {
+            println!(
+                "[FAIL] Solution checker rejected a solution of {} solver:",
+                side_str(*side)
+            );
+            println!("! {solution}");
+            for line in report.lines() {
+                println!("  {line}");
+            }
+        }
+    }
+}
+
+fn report_divergence_rich(divergence: &Divergence) {
+    match divergence {
+        Divergence::Mismatch { index, left, right } => {
+            println!(
+                "\x1b[31m❌ Solution \x1b[1m#{}\x1b[0m\x1b[31m differs, so the solvers do not find the same solutions in the same order:\x1b[0m",
+                index + 1
+            );
+            println!(
+                "\x1b[33m~\x1b[0m {}",
+                word_diff(
+                    left,
+                    right,
+                    ("\x1b[1;31m", "\x1b[0m"),
+                    ("\x1b[1;32m", "\x1b[0m"),
+                )
+            );
+        }
+        Divergence::Extra {
+            index,
+            side,
+            solution,
+        } => {
+            let (marker, color, other) = match side {
+                Side::Left => ('-', 31, "right"),
+                Side::Right => ('+', 32, "left"),
+            };
+            println!(
+                "\x1b[31m❌ Solution \x1b[1m#{}\x1b[0m\x1b[31m of {} solver is missing from the complete search of {other} solver ({}):\x1b[0m",
+                index + 1,
+                side_str(*side),
+                Verdict::extra_on(*side).describe()
+            );
+            println!("\x1b[{color}m{marker} {solution}\x1b[0m");
+        }
+        Divergence::Rejected {
+            side,
+            solution,
+            report,
+        } => {
+            println!(
+                "\x1b[31m❌ Solution checker \x1b[1mrejected\x1b[0m\x1b[31m a solution of {} solver:\x1b[0m",
+                side_str(*side)
+            );
+            println!("\x1b[31m! {solution}\x1b[0m");
+            for line in report.lines() {
+                println!("  \x1b[2m{line}\x1b[0m");
+            }
+        }
+    }
+}
+
+/// Points to the logs written with `--log-dir`, if any.
+pub(crate) fn report_logs(left: &SolverRun, right: &SolverRun, output: OutputMode) {
+    for (side, run) in [("Left", left), ("Right", right)] {
+        let Some(log) = &run.log else {
+            continue;
+        };
+        match output {
+            OutputMode::Ascii => println!("[LOG] {side} run logged to {}", log.display()),
+            OutputMode::Rich => println!(
+                "\x1b[36m📄 {side} run logged to \x1b[1m{}\x1b[0m",
+                log.display()
+            ),
+            OutputMode::None => {}
+        }
+    }
+}
+
+/// Shows the `minizinc` executable of each side and its version, when one was given.
+pub(crate) fn report_minizinc(left: &SolverSpec, right: &SolverSpec, output: OutputMode) {
+    if left.minizinc.is_none() && right.minizinc.is_none() {
+        return;
+    }
+    for (side, spec) in [("Left", left), ("Right", right)] {
+        let executable = spec.executable();
+        let version = minizinc_version(executable).unwrap_or_else(|| "version unknown".to_string());
+        match output {
+            OutputMode::Ascii => println!(
+                "[MINIZINC] {side} solver runs {}: {version}",
+                executable.display()
+            ),
+            OutputMode::Rich => println!(
+                "\x1b[36m🧩 {side} solver runs \x1b[1m{}\x1b[0m\x1b[36m: {version}\x1b[0m",
+                executable.display()
+            ),
+            OutputMode::None => {}
+        }
+    }
+}
+
+/// Warns on stderr that a solver is run differently because it lacks some of the standard
+/// flags.
+pub(crate) fn report_capabilities(
+    solvers: SolverErrorType,
+    solver: &SolverSpec,
+    dropped: &[String],
+    output: OutputMode,
+) {
+    let mut notes = Vec::new();
+    if !solver.capabilities.all_solutions {
+        notes.push(
+            "cannot list all its solutions (`-a`), so only the first one it finds is compared; use --checker to verify that solution"
+                .to_string(),
+        );
+    }
+    if !dropped.is_empty() {
+        notes.push(format!(
+            "does not support `{}`, which is left out",
+            shell_words::join(dropped)
+        ));
+    }
+    let label = match solvers {
+        SolverErrorType::Left => "Left solver",
+        SolverErrorType::Right => "Right solver",
+        SolverErrorType::Both => "Solver",
+    };
+    for note in notes {
+        match output {
+            OutputMode::Ascii => eprintln!("[REDUCED] {label} {} {note}", solver.tag),
+            OutputMode::Rich => eprintln!(
+                "\x1b[33m⚠️  {label} \x1b[1m{}\x1b[0m\x1b[33m {note}\x1b[0m",
+                solver.tag
+            ),
+            OutputMode::None => {}
+        }
+    }
+}
+
+/// Points to the FlatZinc compiled once for both runs.
+pub(crate) fn report_compiled(compiled: &Compiled, solver: &SolverSpec, output: OutputMode) {
+    match output {
+        OutputMode::Ascii => println!(
+            "[COMPILED] Flattened once for {} into {} and {}",
+            solver.describe(),
+            compiled.fzn.display(),
+            compiled.ozn.display()
+        ),
+        OutputMode::Rich => println!(
+            "\x1b[36m🧱 Flattened once for \x1b[1m{}\x1b[0m\x1b[36m into {} and {}\x1b[0m",
+            solver.describe(),
+            compiled.fzn.display(),
+            compiled.ozn.display()
+        ),
         OutputMode::None => {}
+    }
+}
+
+fn print_flatzinc_ascii(stats: (&FznStats, &FznStats), labels: (&str, &str)) {
+    for (label, stats) in [(labels.0, stats.0), (labels.1, stats.1)] {
+        println!(
+            "[FLATZINC] {label}: {} variables, {} arrays, {} constraints",
+            stats.variables, stats.arrays, stats.constraints
+        );
+    }
+    let changes = stats.0.predicate_changes(stats.1);
+    if stats.0 == stats.1 {
+        println!("[OK] Both FlatZinc models have the same size and predicates.");
+    } else if changes.is_empty() {
+        println!("[FLATZINC-DIFF] The FlatZinc models differ in size but use the same predicates.");
+    } else {
+        println!(
+            "[FLATZINC-DIFF] {} predicates are used differently ({} vs {}):",
+            changes.len(),
+            labels.0,
+            labels.1
+        );
+        for (predicate, left, right) in changes {
+            println!("  {predicate}: {left} vs {right}");
+        }
+    }
+}
+
+fn print_flatzinc_rich(stats: (&FznStats, &FznStats), labels: (&str, &str)) {
+    for (label, stats) in [(labels.0, stats.0), (labels.1, stats.1)] {
+        println!(
+            "🧱 {label}: \x1b[1m{}\x1b[0m variables, \x1b[1m{}\x1b[0m arrays, \x1b[1m{}\x1b[0m constraints",
+            stats.variables, stats.arrays, stats.constraints
+        );
+    }
+    let changes = stats.0.predicate_changes(stats.1);
+    if stats.0 == stats.1 {
+        println!("\x1b[32m✅ Both FlatZinc models have the same size and predicates.\x1b[0m");
+    } else if changes.is_empty() {
+        println!(
+            "\x1b[33m🧱 The FlatZinc models differ in size but use the same predicates.\x1b[0m"
+        );
+    } else {
+        println!(
+            "\x1b[33m🧱 \x1b[1m{}\x1b[0m\x1b[33m predicates are used differently ({} vs {}):\x1b[0m",
+            changes.len(),
+            labels.0,
+            labels.1
+        );
+        for (predicate, left, right) in changes {
+            println!("    {predicate}: \x1b[31m{left}\x1b[0m vs \x1b[32m{right}\x1b[0m");
+        }
+    }
+}
+
+/// Compares the size and the predicates of two FlatZinc models, labelled e.g. by the
+/// MiniZinc installation that compiled them.
+pub(crate) fn report_flatzinc(
+    stats: (&FznStats, &FznStats),
+    labels: (&str, &str),
+    output: OutputMode,
+) {
+    match output {
+        OutputMode::Ascii => print_flatzinc_ascii(stats, labels),
+        OutputMode::Rich => print_flatzinc_rich(stats, labels),
+        OutputMode::None => {}
+    }
+}
+
+pub(crate) fn report_saved(path: &Path, output: OutputMode) {
+    match output {
+        OutputMode::Ascii => println!("[SAVED] Reproducer written to {}", path.display()),
+        OutputMode::Rich => println!(
+            "\x1b[36m💾 Reproducer written to \x1b[1m{}\x1b[0m",
+            path.display()
+        ),
+        OutputMode::None => {}
+    }
+}
+
+/// Reports the first solution of a run rejected by the solution checker, if any.
+pub(crate) fn check_rejection(run: &SolverRun, side: Side, output: OutputMode) -> Option<Verdict> {
+    let (solution, report) = run.rejection.clone()?;
+    print_divergence(
+        &Divergence::Rejected {
+            side,
+            solution,
+            report,
+        },
+        output,
+    );
+    Some(Verdict::Rejected(side))
+}
+
+fn print_divergence(divergence: &Divergence, output: OutputMode) {
+    match output {
+        OutputMode::Ascii => report_divergence_ascii(divergence),
+        OutputMode::Rich => report_divergence_rich(divergence),
+        OutputMode::None => {}
+    }
+}
+
+/// Reports a mismatch found while the solvers were still running.
+pub(crate) fn report_divergence(divergence: Divergence, output: OutputMode) -> CheckStatus {
+    print_divergence(&divergence, output);
+    let stopped = CheckStatus {
+        left: Outcome::Stopped,
+        right: Outcome::Stopped,
+        verdict: None,
     };
+    match divergence {
+        Divergence::Mismatch { .. } => stopped,
+        Divergence::Extra { side, .. } => CheckStatus::diff(Verdict::extra_on(side)),
+        Divergence::Rejected { side, .. } => CheckStatus {
+            verdict: Some(Verdict::Rejected(side)),
+            ..stopped
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(left: Outcome, right: Outcome, verdict: Option<Verdict>) -> u8 {
+        CheckStatus {
+            left,
+            right,
+            verdict,
+        }
+        .code()
+    }
+
+    #[test]
+    fn exit_code_encodes_both_outcomes_and_the_verdict() {
+        let crash = Outcome::Failed(FailureKind::Crash);
+        let timeout = Outcome::Failed(FailureKind::Timeout);
+        let complete = Outcome::Complete;
+        assert_eq!(code(complete, complete, None), 0);
+        assert_eq!(code(crash, complete, None), 1);
+        assert_eq!(code(complete, crash, None), 8);
+        assert_eq!(code(crash, timeout, None), 17);
+        assert_eq!(code(complete, complete, Some(Verdict::Subset)), 64);
+        assert_eq!(code(complete, complete, Some(Verdict::Superset)), 128);
+        assert_eq!(code(complete, complete, Some(Verdict::Incomparable)), 192);
+        assert_eq!(
+            code(crash, complete, Some(Verdict::Outside(Side::Left))),
+            129
+        );
+        assert_eq!(
+            code(complete, timeout, Some(Verdict::Outside(Side::Right))),
+            80
+        );
+        assert_eq!(code(complete, Outcome::Slowdown, None), 48);
+    }
 
-    match solvers {
-        SolverErrorType::Left => CheckStatus::CrashLeft,
-        SolverErrorType::Right => CheckStatus::CrashRight,
-        SolverErrorType::Both => CheckStatus::CrashBoth,
+    #[test]
+    fn exit_code_of_runs_stopped_at_the_first_difference() {
+        let stopped = Outcome::Stopped;
+        assert_eq!(code(stopped, stopped, None), 63);
+        assert_eq!(
+            code(stopped, stopped, Some(Verdict::Rejected(Side::Left))),
+            191
+        );
+        assert_eq!(
+            code(stopped, stopped, Some(Verdict::Rejected(Side::Right))),
+            127
+        );
+    }
+
+    #[test]
+    fn lists_all_solutions_unless_reduced() {
+        let reduced = |single_solution| ReportOptions {
+            single_solution,
+            ..ReportOptions::default()
+        };
+        assert!(reduced(None).lists_all(Side::Left));
+        assert!(!reduced(Some(SolverErrorType::Left)).lists_all(Side::Left));
+        assert!(reduced(Some(SolverErrorType::Left)).lists_all(Side::Right));
+        assert!(!reduced(Some(SolverErrorType::Both)).lists_all(Side::Right));
     }
 }
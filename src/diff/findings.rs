@@ -0,0 +1,290 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde_json::json;
+
+use crate::diff::{
+    ResourceLimits, SolverOutput, SolverSpec,
+    display::{CheckStatus, Outcome, Verdict},
+    flatzinc::Compiled,
+    minizinc::minizinc_version,
+};
+
+/// Everything needed to reproduce a non-successful check.
+pub(crate) struct Finding<'a> {
+    pub(crate) model: &'a Path,
+    pub(crate) instance: &'a Path,
+    pub(crate) checker: Option<&'a Path>,
+    /// FlatZinc both solvers were run on, with `--compile-once`
+    pub(crate) compiled: Option<&'a Compiled>,
+    pub(crate) solvers: (&'a SolverSpec, &'a SolverSpec),
+    pub(crate) runs: (&'a SolverOutput, &'a SolverOutput),
+    pub(crate) status: CheckStatus,
+    /// Limits the runs were subject to, which the reproducer applies as well
+    pub(crate) limits: ResourceLimits,
+    pub(crate) timeout: Option<Duration>,
+}
+
+/// Picks a directory for a new finding in `dir`, named after the model and the instance.
+fn finding_dir(dir: &Path, model: &Path, instance: &Path) -> PathBuf {
+    let stem = |path: &Path| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let name = format!("{}-{}", stem(model), stem(instance));
+    let mut path = dir.join(&name);
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = dir.join(format!("{name}-{n}"));
+    }
+    path
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+impl Finding<'_> {
+    /// Input files of the finding, which are copied next to the reproducer.
+    fn inputs(&self) -> impl Iterator<Item = &Path> {
+        let compiled = self.compiled.map(|c| [c.fzn.as_path(), c.ozn.as_path()]);
+        [Some(self.model), Some(self.instance), self.checker]
+            .into_iter()
+            .flatten()
+            .chain(compiled.into_iter().flatten())
+    }
+
+    /// Command line of a run, with the input files replaced by their copies.
+    fn repro_command(&self, command: &[String]) -> String {
+        let words = command.iter().map(|word| {
+            match self.inputs().find(|input| Path::new(word) == *input) {
+                Some(input) => file_name(input),
+                None => word.clone(),
+            }
+        });
+        shell_words::join(words)
+    }
+
+    fn write_repro(&self, path: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "#!/bin/sh")?;
+        writeln!(
+            file,
+            "# Reruns both solvers on the copies of the model and data next to this script."
+        )?;
+        writeln!(file, "cd \"$(dirname \"$0\")\" || exit 1")?;
+        if self.limits.memory.is_some() || self.limits.cpu.is_some() {
+            writeln!(file, "# Resource limits the solvers were run with")?;
+        }
+        if let Some(bytes) = self.limits.memory {
+            writeln!(file, "ulimit -v {}", bytes / 1024)?;
+        }
+        if let Some(secs) = self.limits.cpu {
+            writeln!(file, "ulimit -t {secs}")?;
+        }
+        for (side, spec, run) in [
+            ("left", self.solvers.0, self.runs.0),
+            ("right", self.solvers.1, self.runs.1),
+        ] {
+            writeln!(file)?;
+            let banner = format!("== {side} solver: {}", spec.describe());
+            writeln!(file, "echo {}", shell_words::quote(&banner))?;
+            if run.run().command.is_empty() {
+                writeln!(file, "# `minizinc` could not be started for this solver")?;
+            } else {
+                let mut command = self.repro_command(&run.run().command);
+                // Solvers without `-t` were stopped by a watchdog instead.
+                if let Some(timeout) = self.timeout
+                    && !spec.capabilities.time_limit
+                {
+                    command = format!("timeout {} {command}", timeout.as_secs());
+                }
+                if let Some(bytes) = self.limits.output {
+                    command.push_str(&format!(" | head -c {bytes}"));
+                }
+                writeln!(file, "{command}")?;
+            }
+        }
+        file.flush()?;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+    }
+
+    /// Machine-readable description of the finding, read back by `report-bug`.
+    fn write_json(&self, path: &Path, versions: (&str, &str)) -> io::Result<()> {
+        let side = |spec: &SolverSpec, output: &SolverOutput, outcome: Outcome, version: &str| {
+            let error = match output {
+                SolverOutput::Failed(e, _) => e.describe(),
+                _ => None,
+            };
+            json!({
+                "solver": spec.describe(),
+                "tag": spec.tag,
+                "flags": spec.flags,
+                "executable": spec.executable(),
+                "solver_id": spec.info.as_ref().map(|info| &info.id),
+                "solver_name": spec.info.as_ref().map(|info| &info.name),
+                "solver_version": spec.info.as_ref().map(|info| &info.version),
+                "minizinc": version,
+                "outcome": outcome.describe(),
+                "error": error,
+                "command": self.repro_command(&output.run().command),
+            })
+        };
+        let finding = json!({
+            "model": file_name(self.model),
+            "instance": file_name(self.instance),
+            "checker": self.checker.map(file_name),
+            "fzn": self.compiled.map(|c| file_name(&c.fzn)),
+            "ozn": self.compiled.map(|c| file_name(&c.ozn)),
+            "exit_code": self.status.code(),
+            "verdict": self.status.verdict.map(Verdict::name),
+            "left": side(self.solvers.0, self.runs.0, self.status.left, versions.0),
+            "right": side(self.solvers.1, self.runs.1, self.status.right, versions.1),
+        });
+        let text = serde_json::to_string_pretty(&finding).map_err(io::Error::other)?;
+        fs::write(path, text + "\n")
+    }
+
+    fn write_summary(&self, path: &Path, versions: (&str, &str)) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "# minizinc-diff finding")?;
+        writeln!(file)?;
+        writeln!(file, "- Model: `{}`", file_name(self.model))?;
+        writeln!(file, "- Data: `{}`", file_name(self.instance))?;
+        if let Some(checker) = self.checker {
+            writeln!(file, "- Checker: `{}`", file_name(checker))?;
+        }
+        if let Some(compiled) = self.compiled {
+            writeln!(
+                file,
+                "- Compiled once: `{}` and `{}`",
+                file_name(&compiled.fzn),
+                file_name(&compiled.ozn)
+            )?;
+        }
+        let same_minizinc = self.solvers.0.executable() == self.solvers.1.executable();
+        if same_minizinc {
+            writeln!(file, "- MiniZinc: {}", versions.0)?;
+        }
+        writeln!(file, "- Exit code: {}", self.status.code())?;
+        match self.status.verdict {
+            Some(verdict) => writeln!(file, "- Verdict: mismatch ({})", verdict.describe())?,
+            None if self.status.left == Outcome::Stopped => writeln!(
+                file,
+                "- Verdict: mismatch found while the solvers ran; how their solution sets relate is unknown"
+            )?,
+            None => writeln!(file, "- Verdict: no difference found")?,
+        }
+        writeln!(file)?;
+        writeln!(file, "| | Left | Right |")?;
+        writeln!(file, "| --- | --- | --- |")?;
+        let describe = |output: &SolverOutput, outcome: Outcome| match output {
+            SolverOutput::Failed(e, _) => match e.describe() {
+                Some(description) => format!("{} ({description})", outcome.describe()),
+                None => outcome.describe().to_string(),
+            },
+            _ => outcome.describe().to_string(),
+        };
+        writeln!(
+            file,
+            "| Solver | `{}` | `{}` |",
+            self.solvers.0.describe(),
+            self.solvers.1.describe()
+        )?;
+        if !same_minizinc {
+            writeln!(file, "| MiniZinc | {} | {} |", versions.0, versions.1)?;
+        }
+        writeln!(
+            file,
+            "| Outcome | {} | {} |",
+            describe(self.runs.0, self.status.left),
+            describe(self.runs.1, self.status.right)
+        )?;
+        writeln!(
+            file,
+            "| Solutions | {} | {} |",
+            self.runs.0.run().solutions.len(),
+            self.runs.1.run().solutions.len()
+        )?;
+        writeln!(
+            file,
+            "| Time | {:.3}s | {:.3}s |",
+            self.runs.0.run().duration.as_secs_f64(),
+            self.runs.1.run().duration.as_secs_f64()
+        )?;
+        writeln!(file)?;
+        writeln!(
+            file,
+            "Run `./repro.sh` to rerun both solvers. Their raw output is in `left.stdout`, \
+             `left.stderr`, `right.stdout` and `right.stderr`."
+        )?;
+        let logs: Vec<_> = [("Left", self.runs.0), ("Right", self.runs.1)]
+            .into_iter()
+            .filter_map(|(side, output)| output.run().log.as_ref().map(|log| (side, log)))
+            .collect();
+        if !logs.is_empty() {
+            writeln!(file)?;
+            for (side, log) in logs {
+                writeln!(file, "- {side} run log: `{}`", log.display())?;
+            }
+        }
+        file.flush()
+    }
+
+    /// Writes a self-contained folder for the finding to `dir`: copies of the input files,
+    /// a `repro.sh` script, the raw output of both runs, a `summary.md` and a `finding.json`.
+    pub(crate) fn save(&self, dir: &Path) -> io::Result<PathBuf> {
+        let path = finding_dir(dir, self.model, self.instance);
+        fs::create_dir_all(&path)?;
+        for input in self.inputs() {
+            fs::copy(input, path.join(file_name(input)))?;
+        }
+        for (side, output) in [("left", self.runs.0), ("right", self.runs.1)] {
+            let run = output.run();
+            let mut stdout = File::create(path.join(format!("{side}.stdout")))?;
+            if let Some(kept) = &run.stdout {
+                kept.copy_to(&mut stdout)?;
+            }
+            // A run that could not be started only has the error message.
+            let stderr = match output {
+                SolverOutput::Failed(e, _) if run.stderr.is_empty() => e.message.as_bytes(),
+                _ => &run.stderr,
+            };
+            fs::write(path.join(format!("{side}.stderr")), stderr)?;
+        }
+        self.write_repro(&path.join("repro.sh"))?;
+        let version = |spec: &SolverSpec| {
+            minizinc_version(spec.executable()).unwrap_or_else(|| "unknown".to_string())
+        };
+        let versions = (version(self.solvers.0), version(self.solvers.1));
+        let versions = (versions.0.as_str(), versions.1.as_str());
+        self.write_summary(&path.join("summary.md"), versions)?;
+        self.write_json(&path.join("finding.json"), versions)?;
+        Ok(path)
+    }
+}
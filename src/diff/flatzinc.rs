@@ -0,0 +1,143 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::diff::{SolverSpec, log::sanitise};
+
+/// FlatZinc and output model written by `minizinc -c`.
+#[derive(Debug, Clone)]
+pub(crate) struct Compiled {
+    pub(crate) fzn: PathBuf,
+    pub(crate) ozn: PathBuf,
+}
+
+impl Compiled {
+    /// Paths of the compiled files of `solver` in `dir`, named after the model, the instance
+    /// and the solver.
+    pub(crate) fn new(dir: &Path, model: &Path, instance: &Path, solver: &SolverSpec) -> Compiled {
+        let stem = |path: &Path| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let name = sanitise(&format!(
+            "{}-{}-{}",
+            stem(model),
+            stem(instance),
+            solver.describe()
+        ));
+        Compiled {
+            fzn: dir.join(format!("{name}.fzn")),
+            ozn: dir.join(format!("{name}.ozn")),
+        }
+    }
+}
+
+/// Directory for compiled files when none is given: a folder of this process in the
+/// temporary directory, which is kept so that the files can be inspected afterwards.
+pub(crate) fn default_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("minizinc-diff-{}", std::process::id()))
+}
+
+/// Size of a FlatZinc model and the predicates it uses.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct FznStats {
+    pub(crate) variables: usize,
+    pub(crate) arrays: usize,
+    pub(crate) constraints: usize,
+    /// Number of constraints of each predicate
+    pub(crate) predicates: BTreeMap<String, usize>,
+}
+
+impl FznStats {
+    /// Counts the items of a FlatZinc model as printed by MiniZinc, one item per line.
+    pub(crate) fn parse(fzn: &str) -> FznStats {
+        let mut stats = FznStats::default();
+        for line in fzn.lines().map(str::trim) {
+            if line.starts_with("var ") {
+                stats.variables += 1;
+            } else if line.starts_with("array ") && line.contains(" of var ") {
+                stats.arrays += 1;
+            } else if let Some(constraint) = line.strip_prefix("constraint ") {
+                stats.constraints += 1;
+                let predicate = constraint
+                    .split('(')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                *stats.predicates.entry(predicate).or_default() += 1;
+            }
+        }
+        stats
+    }
+
+    pub(crate) fn read(path: &Path) -> io::Result<FznStats> {
+        Ok(FznStats::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Predicates used a different number of times in the two models, with both counts.
+    pub(crate) fn predicate_changes<'a>(
+        &'a self,
+        other: &'a FznStats,
+    ) -> Vec<(&'a str, usize, usize)> {
+        let mut names: Vec<&str> = self
+            .predicates
+            .keys()
+            .chain(other.predicates.keys())
+            .map(String::as_str)
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| {
+                let count = |stats: &FznStats| stats.predicates.get(name).copied().unwrap_or(0);
+                (name, count(self), count(other))
+            })
+            .filter(|(_, left, right)| left != right)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_flatzinc_items() {
+        let stats = FznStats::parse(
+            "var 1..3: x:: output_var;\n\
+             var bool: b;\n\
+             array [1..2] of var int: xs = [x, x];\n\
+             array [1..2] of int: cs = [1, 2];\n\
+             constraint int_lin_le(cs, xs, 4);\n\
+             constraint int_lin_le(cs, xs, 5);\n\
+             constraint bool2int(b, x);\n\
+             solve satisfy;\n",
+        );
+        assert_eq!(stats.variables, 2);
+        assert_eq!(stats.arrays, 1);
+        assert_eq!(stats.constraints, 3);
+        assert_eq!(stats.predicates["int_lin_le"], 2);
+        assert_eq!(stats.predicates["bool2int"], 1);
+    }
+}
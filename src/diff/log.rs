@@ -0,0 +1,182 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::diff::SolverSpec;
+
+/// Everything recorded about one `minizinc` invocation.
+pub(crate) struct RunLog<'a> {
+    pub(crate) model: &'a Path,
+    pub(crate) instance: &'a Path,
+    pub(crate) solver: &'a SolverSpec,
+    pub(crate) command: &'a [String],
+    pub(crate) started: SystemTime,
+    pub(crate) finished: SystemTime,
+    /// Exit status or signal of the process
+    pub(crate) status: String,
+    pub(crate) stdout: Option<&'a StdoutFile>,
+    pub(crate) stderr: &'a [u8],
+}
+
+/// Raw standard output of a run, streamed to an anonymous temporary file while it is read, so
+/// that the output of long runs is not kept in memory.
+pub(crate) struct StdoutFile {
+    writer: BufWriter<File>,
+}
+
+impl StdoutFile {
+    pub(crate) fn new() -> io::Result<Self> {
+        static N_STDOUT_FILES: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "minizinc-diff-{}-{}.stdout",
+            std::process::id(),
+            N_STDOUT_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        // The file stays accessible through `file` and is gone once it is closed.
+        fs::remove_file(&path)?;
+        Ok(StdoutFile {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    pub(crate) fn push_line(&mut self, line: &str) -> io::Result<()> {
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Writes out the buffered output, once the run is over.
+    pub(crate) fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Copies the whole output to `writer`.
+    pub(crate) fn copy_to(&self, writer: &mut impl Write) -> io::Result<u64> {
+        let mut file = self.writer.get_ref();
+        file.seek(SeekFrom::Start(0))?;
+        io::copy(&mut file, writer)
+    }
+}
+
+/// Formats a point in time as an ISO 8601 UTC timestamp with milliseconds.
+fn utc_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Converts days since the epoch to a civil date, after Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Turns a model, instance or solver name into a part of a file name.
+pub(crate) fn sanitise(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Creates a new log file named after the model, the instance and the solver. Runs that would
+/// share a name, e.g. the repeated runs of `perf`, get a numbered suffix.
+fn create_log_file(dir: &Path, log: &RunLog) -> io::Result<(PathBuf, File)> {
+    let stem = |path: &Path| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let name = sanitise(&format!(
+        "{}-{}-{}",
+        stem(log.model),
+        stem(log.instance),
+        log.solver.describe()
+    ));
+    fs::create_dir_all(dir)?;
+    let mut n = 1;
+    loop {
+        let path = if n == 1 {
+            dir.join(format!("{name}.log"))
+        } else {
+            dir.join(format!("{name}-{n}.log"))
+        };
+        // Both sides of a check may log at the same time, so the name is claimed atomically.
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+impl RunLog<'_> {
+    /// Writes the log to a new file in `dir` and returns its path.
+    pub(crate) fn write(&self, dir: &Path) -> io::Result<PathBuf> {
+        let (path, file) = create_log_file(dir, self)?;
+        let mut file = BufWriter::new(file);
+        let duration = self
+            .finished
+            .duration_since(self.started)
+            .unwrap_or_default();
+        writeln!(file, "command: {}", shell_words::join(self.command))?;
+        writeln!(file, "model: {}", self.model.display())?;
+        writeln!(file, "instance: {}", self.instance.display())?;
+        writeln!(file, "solver: {}", self.solver.describe())?;
+        writeln!(file, "started: {}", utc_timestamp(self.started))?;
+        writeln!(file, "finished: {}", utc_timestamp(self.finished))?;
+        writeln!(file, "duration: {:.3}s", duration.as_secs_f64())?;
+        writeln!(file, "status: {}", self.status)?;
+        writeln!(file, "----- stdout -----")?;
+        if let Some(stdout) = self.stdout {
+            stdout.copy_to(&mut file)?;
+        }
+        writeln!(file, "----- stderr -----")?;
+        file.write_all(self.stderr)?;
+        file.flush()?;
+        Ok(path)
+    }
+}
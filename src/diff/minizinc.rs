@@ -14,72 +14,619 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 use std::{
-    collections::HashSet,
-    path::PathBuf,
-    process::Command,
-    time::{Duration, Instant},
+    fs,
+    os::unix::process::ExitStatusExt,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
 
-use crate::diff::{SolverOutput, SolverSpec};
+use crate::diff::{
+    CrashCategory, FailureKind, ResourceLimits, RunOptions, SolverError, SolverOutput, SolverRun,
+    SolverSpec, SolverStats, Termination,
+    flatzinc::Compiled,
+    log::{RunLog, StdoutFile},
+    process::{Process, ProcessOutput, terminate},
+    solution_set::SolutionSet,
+};
+
+/// Builds the `minizinc` command running `solver` on the model and the instance, or on the
+/// FlatZinc compiled from them if `options.compiled` is set.
+pub(crate) fn solver_command(
+    model: &Path,
+    instance: &Path,
+    solver: &SolverSpec,
+    options: &RunOptions,
+) -> Command {
+    let mut flags: Vec<String> = solver.flags.clone();
+    if let Some(timeout) = options.timeout
+        && solver.capabilities.time_limit
+    {
+        flags.push("-t".into());
+        flags.push(timeout.as_millis().to_string());
+    }
+
+    let mut cmd = Command::new(solver.executable());
+    if solver.capabilities.all_solutions {
+        cmd.arg("-a");
+    }
+    cmd.args(["--statistics", "--output-time", "--solver", &solver.tag]);
+    cmd.args(flags);
+    match &options.compiled {
+        Some(compiled) => {
+            cmd.arg(&compiled.fzn).arg("--ozn-file").arg(&compiled.ozn);
+        }
+        None => {
+            cmd.arg(model);
+            cmd.arg(instance);
+        }
+    }
+    if let Some(checker) = &options.checker {
+        cmd.arg(checker);
+    }
+    cmd
+}
+
+/// Builds the `minizinc -c` command flattening the model and the instance for `solver`.
+pub(crate) fn compile_command(
+    model: &Path,
+    instance: &Path,
+    solver: &SolverSpec,
+    compiled: &Compiled,
+) -> Command {
+    let mut cmd = Command::new(solver.executable());
+    cmd.args(["-c", "--solver", &solver.tag])
+        .args(&solver.flags)
+        .arg(model)
+        .arg(instance)
+        .arg("--fzn")
+        .arg(&compiled.fzn)
+        .arg("--ozn")
+        .arg(&compiled.ozn);
+    cmd
+}
+
+/// Shell form of a command, preceded by the variables it sets and the inherited `MZN_*`
+/// variables read by MiniZinc.
+pub(crate) fn describe_command(cmd: &Command) -> String {
+    let mut env: Vec<(String, String)> = std::env::vars_os()
+        .filter(|(key, _)| key.to_string_lossy().starts_with("MZN_"))
+        .filter(|(key, _)| cmd.get_envs().all(|(set, _)| set != key.as_os_str()))
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+    env.extend(cmd.get_envs().filter_map(|(key, value)| {
+        value.map(|value| {
+            (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+    }));
+    env.sort();
+    let words = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| arg.to_string_lossy().into_owned());
+    env.iter()
+        .map(|(key, value)| format!("{key}={}", shell_words::quote(value)))
+        .chain(std::iter::once(shell_words::join(words)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
 pub(crate) fn run_solver(
     model: PathBuf,
     instance: PathBuf,
     solver: SolverSpec,
-    timeout: Option<Duration>,
-) -> Result<SolverOutput, String> {
+    options: &RunOptions,
+) -> Result<SolverOutput, SolverError> {
     let start_time = Instant::now();
-    let mut flags: Vec<String> = solver.flags;
-    if let Some(timeout) = timeout {
-        flags.push("-t".into());
-        flags.push(timeout.as_millis().to_string());
+    let started = SystemTime::now();
+    let mut cmd = solver_command(&model, &instance, &solver, options);
+    if options.verbose {
+        eprintln!("[COMMAND] {}", describe_command(&cmd));
     }
 
-    let mut cmd = Command::new("minizinc");
-    cmd.args(["-a", "--solver", &solver.tag]);
-    cmd.args(flags);
-    cmd.arg(model);
-    cmd.arg(instance);
-
-    let output = match cmd.output() {
-        Ok(output) => output,
-        Err(e) => return Err(e.to_string()),
+    let command: Vec<String> = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let mut stdout = if options.keep_output || options.log_dir.is_some() {
+        Some(StdoutFile::new()?)
+    } else {
+        None
     };
+    let mut solutions = SolutionSet::new(options.fingerprints)?;
+    let mut process = Process::spawn(&mut cmd, options.limits)?;
+    let monitor = options.monitor.as_ref();
+    if let Some(monitor) = monitor {
+        monitor.started(process.id());
+    }
+    // Solvers without `-t` are stopped by a watchdog once the timeout has passed.
+    let watchdog = options
+        .timeout
+        .filter(|_| !solver.capabilities.time_limit)
+        .map(|timeout| {
+            let (done, finished) = mpsc::channel::<()>();
+            let pid = process.id();
+            let watchdog = thread::spawn(move || {
+                let expired = finished.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout);
+                if expired {
+                    terminate(pid);
+                }
+                expired
+            });
+            (done, watchdog)
+        });
+
+    let mut parser = OutputParser::default();
+    let mut rejection = None;
+    let mut spill_error = None;
+    while let Some(line) = process.next_line() {
+        if let Some(Err(e)) = stdout.as_mut().map(|stdout| stdout.push_line(&line)) {
+            terminate(process.id());
+            spill_error = Some(e);
+            break;
+        }
+        let Some(solution) = parser.feed(&line) else {
+            continue;
+        };
+        if let Some(monitor) = monitor {
+            match &solution.rejection {
+                Some(report) => monitor.rejected(&solution.text, report),
+                None => monitor.solution(&solution.text),
+            }
+        }
+        if let Some(report) = solution.rejection
+            && rejection.is_none()
+        {
+            rejection = Some((solution.text.clone(), report));
+        }
+        if let Err(e) = solutions.insert(solution.text) {
+            terminate(process.id());
+            spill_error = Some(e);
+            break;
+        }
+    }
+    if let Some(monitor) = monitor {
+        if parser.is_complete {
+            monitor.complete();
+        }
+        monitor.exited();
+    }
+
+    // The watchdog is stopped before the process is reaped, so that it never signals a pid
+    // that has been reused.
+    let exited = process.wait_exit();
+    let timed_out = watchdog.is_some_and(|(done, watchdog)| {
+        drop(done);
+        watchdog.join().unwrap_or(false)
+    });
+    let output = process.wait()?;
+    exited?;
+    if let Some(e) = spill_error.or_else(|| stdout.as_mut().and_then(|s| s.finish().err())) {
+        return Err(e.into());
+    }
     let mzn_duration = start_time.elapsed();
 
+    let log = options.log_dir.as_ref().and_then(|dir| {
+        let mut status = termination(output.status)
+            .map(Termination::describe)
+            .unwrap_or_else(|| "unknown".to_string());
+        if output.output_overflow {
+            status.push_str(", output limit exceeded");
+        }
+        let log = RunLog {
+            model: &model,
+            instance: &instance,
+            solver: &solver,
+            command: &command,
+            started,
+            finished: SystemTime::now(),
+            status,
+            stdout: stdout.as_ref(),
+            stderr: &output.stderr,
+        };
+        log.write(dir)
+            .inspect_err(|e| eprintln!("Failed to write a log to {}: {e}", dir.display()))
+            .ok()
+    });
+
+    let failure = (!timed_out && (output.output_overflow || !output.status.success())).then(|| {
+        let kind = classify_failure(&output, options.limits);
+        let message = String::from_utf8_lossy(&output.stderr).into_owned();
+        SolverError {
+            kind,
+            category: (kind == FailureKind::Crash).then(|| crash_category(&message)),
+            message,
+            termination: termination(output.status),
+        }
+    });
+    let found_solution = solutions.len() > 0;
+    let run = Box::new(SolverRun {
+        solutions,
+        duration: mzn_duration,
+        first_solution: parser.first_solution,
+        stats: parser.stats,
+        usage: output.usage,
+        rejection,
+        command,
+        stdout,
+        stderr: output.stderr,
+        log,
+    });
+    Ok(match failure {
+        Some(e) => SolverOutput::Failed(e, run),
+        None if parser.is_complete => SolverOutput::Complete(run),
+        // Without `-a`, the search ends at the first solution.
+        None if !timed_out && found_solution && !solver.capabilities.all_solutions => {
+            SolverOutput::Complete(run)
+        }
+        None => SolverOutput::Timeout(run),
+    })
+}
+
+/// Flattens the model and the instance for `solver` with `minizinc -c`, writing the FlatZinc
+/// and the output model to `dir`.
+pub(crate) fn compile(
+    model: &Path,
+    instance: &Path,
+    solver: &SolverSpec,
+    dir: &Path,
+    verbose: bool,
+) -> Result<Compiled, SolverError> {
+    fs::create_dir_all(dir)?;
+    let compiled = Compiled::new(dir, model, instance, solver);
+    let mut cmd = compile_command(model, instance, solver, &compiled);
+    if verbose {
+        eprintln!("[COMMAND] {}", describe_command(&cmd));
+    }
+    let output = cmd.stdin(Stdio::null()).output()?;
     if !output.status.success() {
-        Err(String::from_utf8_lossy(&output.stderr).into())
+        let message = String::from_utf8_lossy(&output.stderr).into_owned();
+        return Err(SolverError {
+            kind: FailureKind::Crash,
+            category: Some(crash_category(&message)),
+            message,
+            termination: termination(output.status),
+        });
+    }
+    Ok(compiled)
+}
+
+/// Solutions printed in the raw output of a `minizinc` run, in order.
+pub(crate) fn parse_solutions(stdout: &str) -> Vec<String> {
+    let mut parser = OutputParser::default();
+    stdout
+        .lines()
+        .filter_map(|line| parser.feed(line))
+        .map(|solution| solution.text)
+        .collect()
+}
+
+/// First line of `minizinc --version`, if the executable can be run.
+pub(crate) fn minizinc_version(minizinc: &Path) -> Option<String> {
+    let output = Command::new(minizinc).arg("--version").output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(|line| line.trim().to_string())
+}
+
+/// Tells a run that hit one of the resource limits apart from a plain crash.
+fn classify_failure(output: &ProcessOutput, limits: ResourceLimits) -> FailureKind {
+    if output.output_overflow {
+        return FailureKind::OutputOverflow;
+    }
+    let signal = output.status.signal();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+    if let Some(secs) = limits.cpu {
+        // A solver stopped by the limit does not always take `minizinc` down with it, in which
+        // case only the error message of `minizinc` tells. The hard limit kills with SIGKILL.
+        let cpu_time = output.usage.user_time + output.usage.system_time;
+        let killed_at_limit =
+            signal == Some(libc::SIGKILL) && cpu_time >= Duration::from_secs(secs);
+        let cpu_markers = ["sigxcpu", "cpu time limit exceeded"];
+        if signal == Some(libc::SIGXCPU)
+            || killed_at_limit
+            || cpu_markers.iter().any(|m| stderr.contains(m))
+        {
+            return FailureKind::CpuOut;
+        }
+    }
+    if limits.memory.is_some() {
+        let memory_markers = ["bad_alloc", "out of memory", "cannot allocate memory"];
+        if memory_markers.iter().any(|m| stderr.contains(m)) {
+            return FailureKind::Memout;
+        }
+    }
+    FailureKind::Crash
+}
+
+fn termination(status: ExitStatus) -> Option<Termination> {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => Some(Termination::Exit(code)),
+        (None, Some(signal)) => Some(Termination::Signal(signal)),
+        (None, None) => None,
+    }
+}
+
+/// Tells what went wrong in a crash from the error messages of MiniZinc, e.g.
+/// `MiniZinc: type error: undefined identifier` or
+/// `Config exception: no solver with tag foo found`.
+fn crash_category(stderr: &str) -> CrashCategory {
+    let stderr = stderr.to_lowercase();
+    let has = |markers: &[&str]| markers.iter().any(|m| stderr.contains(m));
+    if has(&[
+        "no solver with tag",
+        "solver not found",
+        "cannot find solver",
+    ]) {
+        CrashCategory::SolverNotFound
+    } else if has(&["syntax error"]) && has_model_location(&stderr) {
+        CrashCategory::Syntax
+    } else if has(&["type error"]) {
+        CrashCategory::Type
+    } else if has(&["evaluation error", "flattening error", "assertion failed"]) {
+        CrashCategory::Evaluation
     } else {
-        Ok(parse_solutions(
-            &String::from_utf8_lossy(&output.stdout),
-            mzn_duration,
-        ))
+        CrashCategory::SolverInternal
     }
 }
 
-fn parse_solutions(output: &str, mzn_duration: Duration) -> SolverOutput {
-    let mut solutions = HashSet::new();
-    let mut current_sol = String::new();
-    let mut is_complete = false;
-    for line_raw in output.lines() {
+/// Tells whether an error message points into a model or data file, e.g.
+/// `/tmp/model.mzn:3.5-10:`. The compiler reports its syntax errors this way, while the
+/// FlatZinc parser of a solver failing on the compiled model does not.
+fn has_model_location(stderr: &str) -> bool {
+    [".mzn:", ".dzn:", ".json:", ".mzc:"].iter().any(|ext| {
+        stderr.match_indices(ext).any(|(i, _)| {
+            stderr[i + ext.len()..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_digit())
+        })
+    })
+}
+
+/// Records a `%%%mzn-stat: name=value` line. Solvers that print statistics after every
+/// solution overwrite the earlier values, so the final ones are kept.
+fn parse_stat(stat: &str, stats: &mut SolverStats) {
+    let Some((name, value)) = stat.split_once('=') else {
+        return;
+    };
+    let value = value.trim();
+    let count = || value.parse::<u64>().ok();
+    let seconds = || {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|s| s.is_finite() && *s >= 0.0)
+            .map(Duration::from_secs_f64)
+    };
+    match name.trim() {
+        "nodes" => stats.nodes = count().or(stats.nodes),
+        "failures" => stats.failures = count().or(stats.failures),
+        "propagations" => stats.propagations = count().or(stats.propagations),
+        "restarts" => stats.restarts = count().or(stats.restarts),
+        "flatTime" => stats.flat_time = seconds().or(stats.flat_time),
+        "solveTime" => stats.solve_time = seconds().or(stats.solve_time),
+        _ => {}
+    }
+}
+
+/// A solution, as soon as its `----------` separator has been read.
+struct ParsedSolution {
+    text: String,
+    /// Report of the solution checker, if it has rejected the solution
+    rejection: Option<String>,
+}
+
+/// Incremental parser for the output of `minizinc`, fed one line at a time.
+#[derive(Default)]
+struct OutputParser {
+    stats: SolverStats,
+    first_solution: Option<Duration>,
+    /// Whether a solution separator has been read
+    has_solution: bool,
+    current_sol: String,
+    is_complete: bool,
+    /// Checker report being read, if any
+    checker_report: Option<String>,
+    /// Report of the solution checker on the solution being read, if it is a rejection
+    rejection: Option<String>,
+}
+
+impl OutputParser {
+    /// Ends the checker report being read. MiniZinc prints the report of the solution checker
+    /// just before the solution itself.
+    fn end_checker_report(&mut self) {
+        if let Some(report) = self.checker_report.take()
+            && report.to_uppercase().contains("INCORRECT")
+        {
+            self.rejection = Some(report);
+        }
+    }
+
+    fn feed(&mut self, line_raw: &str) -> Option<ParsedSolution> {
         let line = line_raw.trim();
-        if line.trim().is_empty() || line.starts_with('%') {
-            continue;
+        if let Some(stat) = line.strip_prefix("%%%mzn-stat:") {
+            self.end_checker_report();
+            parse_stat(stat, &mut self.stats);
+        } else if let Some(elapsed) = line.strip_prefix("% time elapsed:") {
+            self.end_checker_report();
+            // `--output-time` prints the time elapsed after each solution, but also after the
+            // final status, which may come without any solution.
+            if self.has_solution && self.first_solution.is_none() {
+                self.first_solution = elapsed
+                    .trim()
+                    .trim_end_matches('s')
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|s| s.is_finite() && *s >= 0.0)
+                    .map(Duration::from_secs_f64);
+            }
+        } else if line.starts_with("% Solution checker report:") {
+            self.end_checker_report();
+            self.checker_report = Some(String::new());
+        } else if let Some(comment) = line.strip_prefix('%') {
+            if let Some(report) = &mut self.checker_report {
+                if !report.is_empty() {
+                    report.push('\n');
+                }
+                report.push_str(comment.trim());
+            }
+        } else if line.is_empty() {
+            // Empty lines are skipped.
         } else if line == "----------" {
-            solutions.insert(current_sol.to_string());
-            current_sol.clear();
-        } else if line == "==========" {
-            is_complete = true;
+            self.end_checker_report();
+            self.has_solution = true;
+            return Some(ParsedSolution {
+                text: std::mem::take(&mut self.current_sol),
+                rejection: self.rejection.take(),
+            });
+        } else if line == "==========" || line == "=====UNSATISFIABLE=====" {
+            self.end_checker_report();
+            self.is_complete = true;
         } else {
-            if !current_sol.is_empty() {
-                current_sol.push('\n');
+            self.end_checker_report();
+            if !self.current_sol.is_empty() {
+                self.current_sol.push('\n');
             }
-            current_sol.push_str(line);
+            self.current_sol.push_str(line);
         }
+        None
     }
-    if is_complete {
-        SolverOutput::Complete(solutions, mzn_duration)
-    } else {
-        SolverOutput::Timeout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::ResourceUsage;
+
+    fn parse(output: &str) -> (OutputParser, Vec<String>) {
+        let mut parser = OutputParser::default();
+        let solutions = output
+            .lines()
+            .filter_map(|line| parser.feed(line))
+            .map(|solution| solution.text)
+            .collect();
+        (parser, solutions)
+    }
+
+    #[test]
+    fn parses_solutions_and_statistics() {
+        let (parser, solutions) = parse(
+            "x = 1;\n----------\nx = 2;\ny = 3;\n----------\n==========\n\
+             %%%mzn-stat: nodes=42\n%%%mzn-stat: failures=7\n%%%mzn-stat: nodes=50\n",
+        );
+        assert_eq!(solutions, vec!["x = 1;", "x = 2;\ny = 3;"]);
+        assert!(parser.is_complete);
+        assert_eq!(parser.stats.nodes, Some(50));
+        assert_eq!(parser.stats.failures, Some(7));
+    }
+
+    #[test]
+    fn reads_checker_rejections() {
+        let mut parser = OutputParser::default();
+        let lines = [
+            "% Solution checker report:",
+            "% INCORRECT",
+            "% x is too large",
+            "x = 9;",
+            "----------",
+        ];
+        let solution = lines.iter().find_map(|line| parser.feed(line)).unwrap();
+        assert_eq!(solution.text, "x = 9;");
+        assert_eq!(
+            solution.rejection.as_deref(),
+            Some("INCORRECT\nx is too large")
+        );
+    }
+
+    #[test]
+    fn parses_flattening_and_solve_times() {
+        let (parser, _) = parse("%%%mzn-stat: flatTime=0.5\n%%%mzn-stat: solveTime=1.25\n");
+        assert_eq!(parser.stats.flat_time, Some(Duration::from_millis(500)));
+        assert_eq!(parser.stats.solve_time, Some(Duration::from_millis(1250)));
+    }
+
+    #[test]
+    fn takes_the_first_solution_time_after_a_solution() {
+        let (parser, _) = parse(
+            "x = 1;\n----------\n% time elapsed: 0.25 s\nx = 2;\n----------\n% time elapsed: 0.50 s\n",
+        );
+        assert_eq!(parser.first_solution, Some(Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn takes_no_first_solution_time_without_a_solution() {
+        let (parser, solutions) = parse("=====UNSATISFIABLE=====\n% time elapsed: 0.10 s\n");
+        assert!(solutions.is_empty());
+        assert!(parser.is_complete);
+        assert_eq!(parser.first_solution, None);
+    }
+
+    #[test]
+    fn classifies_crashes() {
+        assert_eq!(
+            crash_category("/tmp/model.mzn:3.5-10:\nMiniZinc: syntax error: unexpected ;"),
+            CrashCategory::Syntax
+        );
+        // A solver failing to parse the compiled FlatZinc is not a model error.
+        assert_eq!(
+            crash_category("/tmp/mzn-123.fzn:4: syntax error, unexpected ']'"),
+            CrashCategory::SolverInternal
+        );
+        assert_eq!(
+            crash_category("MiniZinc: type error: undefined identifier `y`"),
+            CrashCategory::Type
+        );
+        assert_eq!(
+            crash_category("Config exception: no solver with tag foo found"),
+            CrashCategory::SolverNotFound
+        );
+        assert_eq!(
+            crash_category("terminate called after throwing an instance"),
+            CrashCategory::SolverInternal
+        );
+    }
+
+    #[test]
+    fn classifies_cpu_time_outs_by_signal_or_message() {
+        let limits = ResourceLimits::from_mib(None, Some(10), None);
+        let failed = |status: i32, stderr: &str| ProcessOutput {
+            status: ExitStatus::from_raw(status),
+            stderr: stderr.as_bytes().to_vec(),
+            usage: ResourceUsage {
+                user_time: Duration::from_secs(12),
+                ..ResourceUsage::default()
+            },
+            output_overflow: false,
+        };
+        assert_eq!(
+            classify_failure(&failed(libc::SIGXCPU, ""), limits),
+            FailureKind::CpuOut
+        );
+        assert_eq!(
+            classify_failure(
+                &failed(1 << 8, "=====ERROR=====\nCPU time limit exceeded"),
+                limits
+            ),
+            FailureKind::CpuOut
+        );
+        // A crash after a long run is still a crash.
+        assert_eq!(
+            classify_failure(&failed(libc::SIGSEGV, ""), limits),
+            FailureKind::Crash
+        );
+        assert_eq!(
+            classify_failure(&failed(1 << 8, "MiniZinc: evaluation error"), limits),
+            FailureKind::Crash
+        );
     }
 }
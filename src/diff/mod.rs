@@ -13,18 +13,39 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
-mod display;
-mod minizinc;
+pub(crate) mod catalog;
+pub(crate) mod display;
+pub(crate) mod findings;
+pub(crate) mod flatzinc;
+mod log;
+pub(crate) mod minizinc;
+mod monitor;
+mod process;
+pub(crate) mod solution;
+mod solution_set;
 
 use std::{
-    collections::HashSet, io::IsTerminal, path::PathBuf, sync::mpsc, thread, time::Duration,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
 };
 
-use clap::Args;
+use clap::{Args, ValueEnum};
 
 use crate::diff::{
-    display::{OutputMode, SolverErrorType, print_diff, report_crash},
-    minizinc::run_solver,
+    catalog::{Capabilities, SolverInfo},
+    display::{
+        CheckStatus, Outcome, OutputMode, ReportOptions, SolverErrorType, print_diff,
+        report_capabilities, report_compiled, report_divergence, report_failure, report_logs,
+        report_minizinc, report_saved,
+    },
+    findings::Finding,
+    flatzinc::Compiled,
+    log::StdoutFile,
+    minizinc::{compile, compile_command, describe_command, run_solver, solver_command},
+    monitor::{Divergence, Monitor, MonitorHandle},
+    solution_set::SolutionSet,
 };
 
 #[derive(Args, Debug)]
@@ -45,70 +66,686 @@ pub(crate) struct DiffArgs {
     /// Disables all output
     #[arg(short, long)]
     quiet: bool,
+    /// `minizinc` executable used by the solvers whose spec does not give one with `tag@path`
+    #[arg(long, value_name = "PATH")]
+    minizinc: Option<PathBuf>,
+    /// Address-space limit of each run, in MiB
+    #[arg(long, value_name = "MIB")]
+    mem_limit: Option<u64>,
+    /// CPU time limit of each run, in seconds
+    #[arg(long, value_name = "SECS")]
+    cpu_limit: Option<u64>,
+    /// Limit on the output of each run, in MiB
+    #[arg(long, value_name = "MIB")]
+    output_limit: Option<u64>,
+    /// Compares solutions in the order they are found and stops both solvers at the first difference
+    #[arg(long)]
+    ordered: bool,
+    /// Solution checker model (.mzc); both solvers are stopped once it rejects a solution
+    #[arg(long, value_name = "FILE", conflicts_with = "compile_once")]
+    checker: Option<PathBuf>,
+    /// Keeps only 128-bit fingerprints of the solutions in memory, spilling their text to a temporary file
+    #[arg(long)]
+    fingerprints: bool,
+    /// Summarises mismatches instead of listing every differing solution, showing K examples per side
+    #[arg(long, value_name = "K", num_args = 0..=1, default_missing_value = "5")]
+    summary: Option<usize>,
+    /// Writes the full lists of differing solutions to this file
+    #[arg(long, value_name = "FILE")]
+    diff_file: Option<PathBuf>,
+    /// Saves a folder with everything needed to reproduce a mismatch or failure to this directory
+    #[arg(long, value_name = "DIR")]
+    save_findings: Option<PathBuf>,
+    /// Logs the command line, output, exit status and timestamps of every run to this directory
+    #[arg(long, value_name = "DIR")]
+    log_dir: Option<PathBuf>,
+    /// Prints the command of every run, with the MiniZinc environment variables, to stderr
+    #[arg(short, long)]
+    verbose: bool,
+    /// Prints the commands of both runs without running them
+    #[arg(long)]
+    dry_run: bool,
+    /// Flattens the model once, with the standard globals or else for the given solver, and runs both solvers on the same FlatZinc
+    #[arg(long, value_name = "SOLVER", num_args = 0..=1, require_equals = true, value_parser = parse_solver_spec)]
+    compile_once: Option<Option<SolverSpec>>,
+    /// Directory where the compiled FlatZinc is kept; by default a new temporary directory
+    #[arg(long, value_name = "DIR", requires = "compile_once")]
+    fzn_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct SolverSpec {
     pub(crate) tag: String,
+    /// `minizinc` executable running the solver, if not the one on `$PATH`
+    pub(crate) minizinc: Option<PathBuf>,
     pub(crate) flags: Vec<String>,
+    /// Standard flags supported by the solver, as found by `check_solvers`
+    pub(crate) capabilities: Capabilities,
+    /// The solver selected by the tag, as found by `check_solvers`
+    pub(crate) info: Option<Box<SolverInfo>>,
 }
 
-fn parse_solver_spec(s: &str) -> Result<SolverSpec, String> {
+/// Parses `tag[@/path/to/minizinc][:flags]`. The tag may itself be MiniZinc's `id@version`,
+/// so only an `@` followed by a path with a `/` starts the path to `minizinc`.
+pub(crate) fn parse_solver_spec(s: &str) -> Result<SolverSpec, String> {
     let parts: Vec<&str> = s.splitn(2, ":").collect();
-    let tag = parts[0].to_string();
+    let path_start = parts[0].match_indices('@').map(|(i, _)| i).find(|&i| {
+        let word = parts[0][i + 1..].split('@').next().unwrap_or_default();
+        word.contains('/')
+    });
+    let (tag, minizinc) = match path_start {
+        Some(i) => (
+            parts[0][..i].to_string(),
+            Some(PathBuf::from(&parts[0][i + 1..])),
+        ),
+        None => (parts[0].to_string(), None),
+    };
     let flags = if parts.len() > 1 {
         shell_words::split(parts[1]).map_err(|e| e.to_string())?
     } else {
         vec![]
     };
-    Ok(SolverSpec { tag, flags })
+    Ok(SolverSpec {
+        tag,
+        minizinc,
+        flags,
+        capabilities: Capabilities::default(),
+        info: None,
+    })
 }
 
-pub(crate) enum SolverOutput {
-    Complete(HashSet<String>, Duration),
+impl SolverSpec {
+    /// The spec as it would be given on the command line, e.g. `gecode@/opt/bin/minizinc:-p 4`.
+    pub(crate) fn describe(&self) -> String {
+        let mut spec = self.tag.clone();
+        if let Some(minizinc) = &self.minizinc {
+            spec.push('@');
+            spec.push_str(&minizinc.to_string_lossy());
+        }
+        if !self.flags.is_empty() {
+            spec.push(':');
+            spec.push_str(&shell_words::join(&self.flags));
+        }
+        spec
+    }
+
+    /// Sets the `minizinc` executable given with `--minizinc`, unless the spec has its own.
+    pub(crate) fn default_minizinc(&mut self, minizinc: Option<&Path>) {
+        if self.minizinc.is_none() {
+            self.minizinc = minizinc.map(Path::to_path_buf);
+        }
+    }
+
+    /// Removes `-r` and `-p`, along with their values, if the solver does not support them, as
+    /// MiniZinc would reject the run. Returns the removed flags.
+    fn drop_unsupported_flags(&mut self) -> Vec<String> {
+        let capabilities = self.capabilities;
+        let supported = |flag: &str| match flag {
+            "-r" | "--random-seed" | "--seed" => capabilities.random_seed,
+            "-p" | "--parallel" => capabilities.parallel,
+            _ => true,
+        };
+        // The value is either the next word, e.g. `-p 4`, or joined to the flag, e.g. `-p4`
+        // or `--parallel=4`. Returns the flag and whether its value is joined to it.
+        let split = |word: &str| -> (String, bool) {
+            if let Some((flag, _)) = word.split_once('=') {
+                return (flag.to_string(), true);
+            }
+            match word.get(..2) {
+                Some(flag @ ("-r" | "-p"))
+                    if word.len() > 2 && word[2..].bytes().all(|b| b.is_ascii_digit()) =>
+                {
+                    (flag.to_string(), true)
+                }
+                _ => (word.to_string(), false),
+            }
+        };
+        let mut kept = Vec::new();
+        let mut dropped = Vec::new();
+        let mut flags = std::mem::take(&mut self.flags).into_iter();
+        while let Some(word) = flags.next() {
+            let (flag, joined) = split(&word);
+            if supported(&flag) {
+                kept.push(word);
+            } else {
+                dropped.push(word);
+                if !joined {
+                    dropped.extend(flags.next());
+                }
+            }
+        }
+        self.flags = kept;
+        dropped
+    }
+
+    /// The `minizinc` executable running the solver.
+    pub(crate) fn executable(&self) -> &Path {
+        self.minizinc
+            .as_deref()
+            .unwrap_or_else(|| Path::new("minizinc"))
+    }
+}
+
+/// Limits applied to the `minizinc` process of every run: the memory and CPU time limits with
+/// `setrlimit`, and the output limit by counting the bytes read from its standard output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ResourceLimits {
+    /// Address-space limit in bytes
+    pub(crate) memory: Option<u64>,
+    /// CPU time limit in seconds
+    pub(crate) cpu: Option<u64>,
+    /// Limit in bytes on the standard output
+    pub(crate) output: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Limits as given on the command line, with the memory and output limits in MiB.
+    pub(crate) fn from_mib(memory: Option<u64>, cpu: Option<u64>, output: Option<u64>) -> Self {
+        const MIB: u64 = 1024 * 1024;
+        ResourceLimits {
+            memory: memory.map(|mib| mib * MIB),
+            cpu,
+            output: output.map(|mib| mib * MIB),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct RunOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) limits: ResourceLimits,
+    /// Solution checker (`.mzc`) passed to MiniZinc along with the model
+    pub(crate) checker: Option<PathBuf>,
+    /// Whether to keep only fingerprints of the solutions in memory
+    pub(crate) fingerprints: bool,
+    /// Whether to keep the raw standard output of the run
+    pub(crate) keep_output: bool,
+    /// Directory where a log of the run is written
+    pub(crate) log_dir: Option<PathBuf>,
+    /// Whether to print the command of the run to stderr
+    pub(crate) verbose: bool,
+    /// FlatZinc run instead of the model and the instance
+    pub(crate) compiled: Option<Compiled>,
+    pub(crate) monitor: Option<MonitorHandle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub(crate) enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    pub(crate) fn other(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+/// Ways in which a solver run can end without a complete answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FailureKind {
+    Crash,
     Timeout,
+    Memout,
+    CpuOut,
+    OutputOverflow,
 }
 
-pub(crate) fn run(args: DiffArgs) -> std::process::ExitCode {
-    let rich_output = std::io::stdout().is_terminal();
-    let output_mode = if args.quiet {
-        OutputMode::None
-    } else if rich_output {
-        OutputMode::Rich
-    } else {
-        OutputMode::Ascii
+/// How a `minizinc` process ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Termination {
+    Exit(i32),
+    Signal(i32),
+}
+
+impl Termination {
+    pub(crate) fn describe(self) -> String {
+        match self {
+            Termination::Exit(code) => format!("exit code {code}"),
+            Termination::Signal(signal) => match signal_name(signal) {
+                Some(name) => format!("signal {name}"),
+                None => format!("signal {signal}"),
+            },
+        }
+    }
+}
+
+fn signal_name(signal: i32) -> Option<&'static str> {
+    Some(match signal {
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGILL => "SIGILL",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGTERM => "SIGTERM",
+        libc::SIGXCPU => "SIGXCPU",
+        libc::SIGXFSZ => "SIGXFSZ",
+        _ => return None,
+    })
+}
+
+/// What went wrong in a crash, as told by the error messages of MiniZinc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum CrashCategory {
+    Syntax,
+    Type,
+    /// Evaluation or flattening error
+    Evaluation,
+    SolverNotFound,
+    /// Any other crash, e.g. a segfault or an abort of the solver
+    SolverInternal,
+}
+
+impl CrashCategory {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            CrashCategory::Syntax => "syntax error",
+            CrashCategory::Type => "type error",
+            CrashCategory::Evaluation => "evaluation error",
+            CrashCategory::SolverNotFound => "solver not found",
+            CrashCategory::SolverInternal => "solver internal error",
+        }
+    }
+
+    /// Whether the crash is caused by the model or the data rather than by the solver.
+    pub(crate) fn is_model_error(self) -> bool {
+        matches!(
+            self,
+            CrashCategory::Syntax | CrashCategory::Type | CrashCategory::Evaluation
+        )
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct SolverError {
+    pub(crate) kind: FailureKind,
+    /// Error message of MiniZinc, if any
+    pub(crate) message: String,
+    /// How the `minizinc` process ended, if it was started at all
+    pub(crate) termination: Option<Termination>,
+    /// What went wrong, for crashes
+    pub(crate) category: Option<CrashCategory>,
+}
+
+impl SolverError {
+    /// Short description of the category and the termination of the failure, if known,
+    /// e.g. `type error, exit code 1`.
+    pub(crate) fn describe(&self) -> Option<String> {
+        let parts: Vec<String> = self
+            .category
+            .map(|c| c.name().to_string())
+            .into_iter()
+            .chain(self.termination.map(Termination::describe))
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+impl From<std::io::Error> for SolverError {
+    fn from(e: std::io::Error) -> Self {
+        SolverError {
+            kind: FailureKind::Crash,
+            message: e.to_string(),
+            termination: None,
+            category: None,
+        }
+    }
+}
+
+/// Search statistics printed by MiniZinc as `%%%mzn-stat:` lines when run with `--statistics`.
+/// A statistic is `None` if neither MiniZinc nor the solver reported it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SolverStats {
+    pub(crate) nodes: Option<u64>,
+    pub(crate) failures: Option<u64>,
+    pub(crate) propagations: Option<u64>,
+    pub(crate) restarts: Option<u64>,
+    pub(crate) flat_time: Option<Duration>,
+    pub(crate) solve_time: Option<Duration>,
+}
+
+/// Resources consumed by a `minizinc` process and the solver processes it has spawned.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ResourceUsage {
+    pub(crate) user_time: Duration,
+    pub(crate) system_time: Duration,
+    /// Peak resident set size in bytes
+    pub(crate) max_rss: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct SolverRun {
+    pub(crate) solutions: SolutionSet,
+    /// Wall-clock time of the whole `minizinc` invocation
+    pub(crate) duration: Duration,
+    /// Time elapsed until the first solution was printed, as per `--output-time`
+    pub(crate) first_solution: Option<Duration>,
+    pub(crate) stats: SolverStats,
+    pub(crate) usage: ResourceUsage,
+    /// First solution rejected by the solution checker, with the checker's report
+    pub(crate) rejection: Option<(String, String)>,
+    /// Command line of the `minizinc` invocation
+    pub(crate) command: Vec<String>,
+    /// Raw standard output, if it was kept
+    pub(crate) stdout: Option<StdoutFile>,
+    pub(crate) stderr: Vec<u8>,
+    /// Log file of the run, if one was written
+    pub(crate) log: Option<PathBuf>,
+}
+
+impl SolverRun {
+    /// Time spent by MiniZinc compiling the model to FlatZinc.
+    pub(crate) fn flattening_time(&self) -> Option<Duration> {
+        self.stats.flat_time
+    }
+
+    /// Time spent by the solver itself. If the solver does not report it, it is estimated
+    /// as the part of the wall-clock time that was not spent flattening.
+    pub(crate) fn solving_time(&self) -> Option<Duration> {
+        self.stats.solve_time.or_else(|| {
+            self.stats
+                .flat_time
+                .map(|flat_time| self.duration.saturating_sub(flat_time))
+        })
+    }
+}
+
+pub(crate) enum SolverOutput {
+    Complete(Box<SolverRun>),
+    /// The solver ran out of time, with the solutions it found before that
+    Timeout(Box<SolverRun>),
+    /// The solver failed, with the solutions it found before that
+    Failed(SolverError, Box<SolverRun>),
+}
+
+impl SolverOutput {
+    /// The run, whether it is complete or not.
+    pub(crate) fn run(&self) -> &SolverRun {
+        match self {
+            SolverOutput::Complete(run)
+            | SolverOutput::Timeout(run)
+            | SolverOutput::Failed(_, run) => run,
+        }
+    }
+
+    pub(crate) fn outcome(&self) -> Outcome {
+        match self {
+            SolverOutput::Complete(_) => Outcome::Complete,
+            SolverOutput::Timeout(_) => Outcome::Failed(FailureKind::Timeout),
+            SolverOutput::Failed(e, _) => Outcome::Failed(e.kind),
+        }
+    }
+}
+
+impl From<SolverError> for SolverOutput {
+    /// A run that failed before producing any solution, e.g. as `minizinc` could not
+    /// be started.
+    fn from(e: SolverError) -> Self {
+        SolverOutput::Failed(e, Box::default())
+    }
+}
+
+/// Checks that the solver tag is known to its `minizinc` before anything is run, so that a typo
+/// fails early, with a suggestion, instead of showing up as a crash of the run. Also records
+/// the standard flags the solver supports, leaving out those of its flags it does not.
+pub(crate) fn check_solver(
+    spec: &mut SolverSpec,
+    solvers: SolverErrorType,
+    output: OutputMode,
+) -> Result<(), SolverError> {
+    // Without a list of solvers, e.g. from a MiniZinc that cannot be run, the runs tell.
+    let Ok(known) = catalog::load(spec.executable()) else {
+        return Ok(());
     };
-    let timeout = args.timeout_secs.map(Duration::from_secs);
+    if let Some(info) = catalog::find(&known, &spec.tag) {
+        spec.capabilities = info.capabilities();
+        spec.info = Some(Box::new(info.clone()));
+        let dropped = spec.drop_unsupported_flags();
+        report_capabilities(solvers, spec, &dropped, output);
+        return Ok(());
+    }
+    let mut message = format!(
+        "no solver with tag `{}` is known to `{}`",
+        spec.tag,
+        spec.executable().display()
+    );
+    match catalog::suggest(&known, &spec.tag) {
+        Some(name) => message.push_str(&format!(", did you mean `{name}`?")),
+        None => message.push('.'),
+    }
+    message.push_str(" Run `minizinc-diff solvers` to list the installed solvers.");
+    Err(SolverError {
+        kind: FailureKind::Crash,
+        message,
+        termination: None,
+        category: Some(CrashCategory::SolverNotFound),
+    })
+}
+
+/// Checks both solvers with `check_solver`, reporting a failure of either.
+pub(crate) fn check_solvers(
+    left: &mut SolverSpec,
+    right: &mut SolverSpec,
+    output: OutputMode,
+) -> Result<(), CheckStatus> {
+    match (
+        check_solver(left, SolverErrorType::Left, output),
+        check_solver(right, SolverErrorType::Right, output),
+    ) {
+        (Ok(()), Ok(())) => Ok(()),
+        (Err(e), Ok(())) => Err(report_failure(e, SolverErrorType::Left, output)),
+        (Ok(()), Err(e)) => Err(report_failure(e, SolverErrorType::Right, output)),
+        (Err(left), Err(right)) => {
+            let e = SolverError {
+                message: format!("\nLeft: {}\nRight: {}", left.message, right.message),
+                ..left
+            };
+            Err(report_failure(e, SolverErrorType::Both, output))
+        }
+    }
+}
+
+/// The solvers that cannot list all solutions and stop at their first one, as found by
+/// `check_solvers`.
+pub(crate) fn single_solution_solvers(
+    left: &SolverSpec,
+    right: &SolverSpec,
+) -> Option<SolverErrorType> {
+    match (
+        left.capabilities.all_solutions,
+        right.capabilities.all_solutions,
+    ) {
+        (true, true) => None,
+        (false, true) => Some(SolverErrorType::Left),
+        (true, false) => Some(SolverErrorType::Right),
+        (false, false) => Some(SolverErrorType::Both),
+    }
+}
+
+/// Runs both solvers at the same time on the same model and instance. With `ordered`,
+/// the solutions are compared in the order they are found and both runs are stopped at the
+/// first difference, which is then returned along with the runs.
+pub(crate) fn run_pair(
+    model: &Path,
+    instance: &Path,
+    solver_left: SolverSpec,
+    solver_right: SolverSpec,
+    options: &RunOptions,
+    ordered: bool,
+) -> (SolverOutput, SolverOutput, Option<Divergence>) {
+    let monitor = Monitor::new(ordered);
     let (tx1, rx1) = mpsc::channel();
     let (tx2, rx2) = mpsc::channel();
 
-    let model_left = args.model.clone();
-    let model_right = args.model.clone();
-    let instance_left = args.instance.clone();
-    let instance_right = args.instance.clone();
+    let model_left = model.to_path_buf();
+    let model_right = model.to_path_buf();
+    let instance_left = instance.to_path_buf();
+    let instance_right = instance.to_path_buf();
+    let options_left = RunOptions {
+        monitor: Some(monitor.handle(Side::Left)),
+        ..options.clone()
+    };
+    let options_right = RunOptions {
+        monitor: Some(monitor.handle(Side::Right)),
+        ..options.clone()
+    };
 
     thread::spawn(move || {
-        let res = run_solver(model_left, instance_left, args.solver_left, timeout);
+        let res = run_solver(model_left, instance_left, solver_left, &options_left);
         tx1.send(res).unwrap();
     });
 
     thread::spawn(move || {
-        let res = run_solver(model_right, instance_right, args.solver_right, timeout);
+        let res = run_solver(model_right, instance_right, solver_right, &options_right);
         tx2.send(res).unwrap();
     });
 
-    let res1 = rx1.recv().unwrap();
-    let res2 = rx2.recv().unwrap();
-
-    let status = match (res1, res2) {
-        (Ok(set1), Ok(set2)) => print_diff(&set1, &set2, output_mode),
-        (Err(e), Ok(_)) => report_crash(e, SolverErrorType::Left, output_mode),
-        (Ok(_), Err(e)) => report_crash(e, SolverErrorType::Right, output_mode),
-        (Err(e_left), Err(e_right)) => report_crash(
-            format!("\nLeft: {e_left}\nRight: {e_right}").to_string(),
-            SolverErrorType::Both,
-            output_mode,
-        ),
+    let res1 = rx1.recv().unwrap().unwrap_or_else(SolverOutput::from);
+    let res2 = rx2.recv().unwrap().unwrap_or_else(SolverOutput::from);
+    (res1, res2, monitor.divergence())
+}
+
+pub(crate) fn run(mut args: DiffArgs) -> std::process::ExitCode {
+    args.solver_left.default_minizinc(args.minizinc.as_deref());
+    args.solver_right.default_minizinc(args.minizinc.as_deref());
+    let output_mode = OutputMode::new(args.quiet);
+    if let Err(status) = check_solvers(&mut args.solver_left, &mut args.solver_right, output_mode) {
+        return status.into();
+    }
+    let report_options = ReportOptions {
+        summary: args.summary,
+        diff_file: args.diff_file,
+        single_solution: single_solution_solvers(&args.solver_left, &args.solver_right),
     };
+    // By default, the model is flattened with the standard decompositions, so that neither
+    // solver runs on the redefinitions of the other.
+    let compile_for = args.compile_once.clone().map(|solver| {
+        let mut solver = solver.unwrap_or_else(|| {
+            let mut solver = args.solver_left.clone();
+            solver.flags.extend(["-G".to_string(), "std".to_string()]);
+            solver
+        });
+        solver.default_minizinc(args.minizinc.as_deref());
+        solver
+    });
+    let fzn_dir = args.fzn_dir.clone().unwrap_or_else(flatzinc::default_dir);
+    let mut options = RunOptions {
+        timeout: args.timeout_secs.map(Duration::from_secs),
+        limits: ResourceLimits::from_mib(args.mem_limit, args.cpu_limit, args.output_limit),
+        checker: args.checker,
+        fingerprints: args.fingerprints,
+        keep_output: args.save_findings.is_some(),
+        log_dir: args.log_dir,
+        verbose: args.verbose,
+        compiled: None,
+        monitor: None,
+    };
+    if args.dry_run {
+        if let Some(solver) = &compile_for {
+            let compiled = Compiled::new(&fzn_dir, &args.model, &args.instance, solver);
+            let cmd = compile_command(&args.model, &args.instance, solver, &compiled);
+            println!("{}", describe_command(&cmd));
+            options.compiled = Some(compiled);
+        }
+        for solver in [&args.solver_left, &args.solver_right] {
+            let cmd = solver_command(&args.model, &args.instance, solver, &options);
+            println!("{}", describe_command(&cmd));
+        }
+        return std::process::ExitCode::SUCCESS;
+    }
+    report_minizinc(&args.solver_left, &args.solver_right, output_mode);
+    if let Some(solver) = &compile_for {
+        match compile(&args.model, &args.instance, solver, &fzn_dir, args.verbose) {
+            Ok(compiled) => {
+                report_compiled(&compiled, solver, output_mode);
+                options.compiled = Some(compiled);
+            }
+            Err(e) => return report_failure(e, SolverErrorType::Both, output_mode).into(),
+        }
+    }
+    let (res1, res2, divergence) = run_pair(
+        &args.model,
+        &args.instance,
+        args.solver_left.clone(),
+        args.solver_right.clone(),
+        &options,
+        args.ordered,
+    );
+
+    let status = match divergence {
+        Some(divergence) => report_divergence(divergence, output_mode),
+        None => print_diff(&res1, &res2, output_mode, &report_options),
+    };
+    report_logs(res1.run(), res2.run(), output_mode);
+
+    if let Some(dir) = &args.save_findings
+        && status != CheckStatus::SUCCESS
+    {
+        let finding = Finding {
+            model: &args.model,
+            instance: &args.instance,
+            checker: options.checker.as_deref(),
+            compiled: options.compiled.as_ref(),
+            solvers: (&args.solver_left, &args.solver_right),
+            runs: (&res1, &res2),
+            status,
+            limits: options.limits,
+            timeout: options.timeout,
+        };
+        match finding.save(dir) {
+            Ok(path) => report_saved(&path, output_mode),
+            Err(e) => eprintln!("Failed to save the finding to {}: {e}", dir.display()),
+        }
+    }
     status.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_solver_specs() {
+        let spec = parse_solver_spec("gecode").unwrap();
+        assert_eq!((spec.tag.as_str(), spec.minizinc), ("gecode", None));
+        let spec = parse_solver_spec("gecode@6.3.0:-p 2 --free-search").unwrap();
+        assert_eq!(spec.tag, "gecode@6.3.0");
+        assert_eq!(spec.minizinc, None);
+        assert_eq!(spec.flags, ["-p", "2", "--free-search"]);
+        let spec = parse_solver_spec("gecode@6.3.0@./bin/minizinc").unwrap();
+        assert_eq!(spec.tag, "gecode@6.3.0");
+        assert_eq!(spec.minizinc, Some(PathBuf::from("./bin/minizinc")));
+        assert!(parse_solver_spec("gecode:'unclosed").is_err());
+    }
+
+    #[test]
+    fn drops_unsupported_flags_in_all_forms() {
+        let mut spec =
+            parse_solver_spec("gecode:-p 4 -p4 --parallel=4 -r 1 -r1 --seed=1 -f").unwrap();
+        spec.capabilities = Capabilities {
+            random_seed: false,
+            parallel: false,
+            ..Capabilities::default()
+        };
+        let dropped = spec.drop_unsupported_flags();
+        assert_eq!(spec.flags, ["-f"]);
+        assert_eq!(
+            dropped,
+            [
+                "-p",
+                "4",
+                "-p4",
+                "--parallel=4",
+                "-r",
+                "1",
+                "-r1",
+                "--seed=1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,277 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use crate::diff::{
+    Side,
+    process::terminate,
+    solution_set::{Fingerprint, fingerprint},
+};
+
+/// A mismatch that is certain before both solvers have finished.
+pub(crate) enum Divergence {
+    /// In ordered mode, the solutions found at the same position differ.
+    Mismatch {
+        index: usize,
+        left: String,
+        right: String,
+    },
+    /// In ordered mode, one solver has found a solution after the other has completed its
+    /// search with fewer solutions.
+    Extra {
+        index: usize,
+        side: Side,
+        solution: String,
+    },
+    /// The solution checker has rejected a solution.
+    Rejected {
+        side: Side,
+        solution: String,
+        report: String,
+    },
+}
+
+#[derive(Default)]
+struct MonitorState {
+    /// Solutions of the solver that is ahead in ordered mode, not yet matched by the other one.
+    pending: VecDeque<(Fingerprint, String)>,
+    pending_side: Option<Side>,
+    n_matched: usize,
+    complete: [bool; 2],
+    pids: [Option<u32>; 2],
+    divergence: Option<Divergence>,
+}
+
+/// Watches the solutions of both solvers as they arrive and stops both runs as soon as
+/// a mismatch is certain.
+pub(crate) struct Monitor {
+    ordered: bool,
+    state: Mutex<MonitorState>,
+}
+
+/// The view of a [`Monitor`] from the run of one solver.
+#[derive(Clone)]
+pub(crate) struct MonitorHandle {
+    monitor: Arc<Monitor>,
+    side: Side,
+}
+
+fn slot(side: Side) -> usize {
+    match side {
+        Side::Left => 0,
+        Side::Right => 1,
+    }
+}
+
+impl Monitor {
+    /// In `ordered` mode, both solvers must find the same solutions in the same order.
+    pub(crate) fn new(ordered: bool) -> Arc<Self> {
+        Arc::new(Monitor {
+            ordered,
+            state: Mutex::new(MonitorState::default()),
+        })
+    }
+
+    pub(crate) fn handle(self: &Arc<Self>, side: Side) -> MonitorHandle {
+        MonitorHandle {
+            monitor: Arc::clone(self),
+            side,
+        }
+    }
+
+    pub(crate) fn divergence(&self) -> Option<Divergence> {
+        self.state.lock().unwrap().divergence.take()
+    }
+}
+
+impl MonitorState {
+    fn diverge(&mut self, divergence: Divergence) {
+        if self.divergence.is_some() {
+            return;
+        }
+        self.divergence = Some(divergence);
+        for pid in self.pids.iter().flatten() {
+            terminate(*pid);
+        }
+    }
+}
+
+impl MonitorHandle {
+    pub(crate) fn started(&self, pid: u32) {
+        let mut state = self.monitor.state.lock().unwrap();
+        state.pids[slot(self.side)] = Some(pid);
+        if state.divergence.is_some() {
+            terminate(pid);
+        }
+    }
+
+    /// Must be called once the output of the process has been consumed, before it is reaped,
+    /// so that a recycled pid is never signalled.
+    pub(crate) fn exited(&self) {
+        self.monitor.state.lock().unwrap().pids[slot(self.side)] = None;
+    }
+
+    pub(crate) fn solution(&self, solution: &str) {
+        if !self.monitor.ordered {
+            return;
+        }
+        let mut state = self.monitor.state.lock().unwrap();
+        let hash = fingerprint(solution);
+        let other = self.side.other();
+        if state.pending_side != Some(other) || state.pending.is_empty() {
+            let index = state.n_matched + state.pending.len();
+            if state.complete[slot(other)] {
+                state.diverge(Divergence::Extra {
+                    index,
+                    side: self.side,
+                    solution: solution.to_string(),
+                });
+                return;
+            }
+            state.pending_side = Some(self.side);
+            state.pending.push_back((hash, solution.to_string()));
+            return;
+        }
+
+        let (other_hash, other_solution) = state.pending.pop_front().expect("not empty");
+        if other_hash == hash {
+            state.n_matched += 1;
+            return;
+        }
+        let (left, right) = match self.side {
+            Side::Left => (solution.to_string(), other_solution),
+            Side::Right => (other_solution, solution.to_string()),
+        };
+        let index = state.n_matched;
+        state.diverge(Divergence::Mismatch { index, left, right });
+    }
+
+    pub(crate) fn rejected(&self, solution: &str, report: &str) {
+        let mut state = self.monitor.state.lock().unwrap();
+        state.diverge(Divergence::Rejected {
+            side: self.side,
+            solution: solution.to_string(),
+            report: report.to_string(),
+        });
+    }
+
+    /// Records that the solver has completed its search.
+    pub(crate) fn complete(&self) {
+        let mut state = self.monitor.state.lock().unwrap();
+        state.complete[slot(self.side)] = true;
+        if !self.monitor.ordered || state.pending_side != Some(self.side.other()) {
+            return;
+        }
+        if let Some((_, solution)) = state.pending.pop_front() {
+            let index = state.n_matched;
+            let side = self.side.other();
+            state.diverge(Divergence::Extra {
+                index,
+                side,
+                solution,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handles(ordered: bool) -> (Arc<Monitor>, MonitorHandle, MonitorHandle) {
+        let monitor = Monitor::new(ordered);
+        let (left, right) = (monitor.handle(Side::Left), monitor.handle(Side::Right));
+        (monitor, left, right)
+    }
+
+    #[test]
+    fn same_solutions_in_the_same_order_do_not_diverge() {
+        let (monitor, left, right) = handles(true);
+        left.solution("x = 1;");
+        left.solution("x = 2;");
+        right.solution("x = 1;");
+        right.solution("x = 2;");
+        left.complete();
+        right.complete();
+        assert!(monitor.divergence().is_none());
+    }
+
+    #[test]
+    fn detects_a_solution_at_a_different_position() {
+        let (monitor, left, right) = handles(true);
+        left.solution("x = 1;");
+        right.solution("x = 1;");
+        right.solution("x = 3;");
+        left.solution("x = 2;");
+        let Some(Divergence::Mismatch { index, left, right }) = monitor.divergence() else {
+            panic!("expected a mismatch");
+        };
+        assert_eq!(
+            (index, left.as_str(), right.as_str()),
+            (1, "x = 2;", "x = 3;")
+        );
+    }
+
+    #[test]
+    fn detects_an_extra_solution_after_the_other_search_completed() {
+        let (monitor, left, right) = handles(true);
+        left.solution("x = 1;");
+        right.solution("x = 1;");
+        right.complete();
+        left.solution("x = 2;");
+        let Some(Divergence::Extra {
+            index,
+            side,
+            solution,
+        }) = monitor.divergence()
+        else {
+            panic!("expected an extra solution");
+        };
+        assert_eq!((index, side, solution.as_str()), (1, Side::Left, "x = 2;"));
+    }
+
+    #[test]
+    fn detects_a_pending_solution_once_the_other_search_completes() {
+        let (monitor, left, right) = handles(true);
+        right.solution("x = 1;");
+        left.complete();
+        let Some(Divergence::Extra { index, side, .. }) = monitor.divergence() else {
+            panic!("expected an extra solution");
+        };
+        assert_eq!((index, side), (0, Side::Right));
+    }
+
+    #[test]
+    fn ignores_the_order_unless_ordered() {
+        let (monitor, left, right) = handles(false);
+        left.solution("x = 1;");
+        right.solution("x = 2;");
+        right.complete();
+        left.solution("x = 3;");
+        assert!(monitor.divergence().is_none());
+        left.rejected("x = 3;", "INCORRECT");
+        assert!(matches!(
+            monitor.divergence(),
+            Some(Divergence::Rejected {
+                side: Side::Left,
+                ..
+            })
+        ));
+    }
+}
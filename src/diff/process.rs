@@ -0,0 +1,230 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    os::unix::process::{CommandExt, ExitStatusExt},
+    process::{Child, ChildStdout, Command, ExitStatus, Stdio},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::diff::{ResourceLimits, ResourceUsage};
+
+/// Everything a finished process has left behind, apart from its standard output, which is
+/// consumed while the process runs.
+pub(crate) struct ProcessOutput {
+    pub(crate) status: ExitStatus,
+    pub(crate) stderr: Vec<u8>,
+    pub(crate) usage: ResourceUsage,
+    /// Whether the process was stopped for printing more than the output limit
+    pub(crate) output_overflow: bool,
+}
+
+fn timeval_to_duration(tv: libc::timeval) -> Duration {
+    Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+}
+
+/// Waits for the process `pid` to exit and collects its resource usage. The usage covers the
+/// process itself and all of its descendants that it has waited for, i.e., for `minizinc`, the
+/// solver process it spawns as well.
+fn wait_with_usage(pid: u32) -> io::Result<(ExitStatus, ResourceUsage)> {
+    let mut status = 0;
+    // SAFETY: `rusage` is a plain C struct for which all-zeroes is a valid value.
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: both pointers are valid for writes for the duration of the call.
+        let res = unsafe { libc::wait4(pid as libc::pid_t, &mut status, 0, &mut rusage) };
+        if res >= 0 {
+            break;
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+
+    // `ru_maxrss` is in kilobytes on Linux and in bytes on macOS.
+    let rss_unit = if cfg!(target_os = "macos") { 1 } else { 1024 };
+    let usage = ResourceUsage {
+        user_time: timeval_to_duration(rusage.ru_utime),
+        system_time: timeval_to_duration(rusage.ru_stime),
+        max_rss: rusage.ru_maxrss as u64 * rss_unit,
+    };
+    Ok((ExitStatus::from_raw(status), usage))
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+type Resource = libc::c_int;
+
+fn set_limit(resource: Resource, soft: u64, hard: u64) -> io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: soft as libc::rlim_t,
+        rlim_max: hard as libc::rlim_t,
+    };
+    // SAFETY: `limit` is a valid `rlimit` and `setrlimit` is async-signal-safe.
+    if unsafe { libc::setrlimit(resource, &limit) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Makes the spawned process (and the solver it starts, which inherits the limits) subject to
+/// the given resource limits.
+fn apply_limits(cmd: &mut Command, limits: ResourceLimits) {
+    if limits.memory.is_none() && limits.cpu.is_none() {
+        return;
+    }
+    // SAFETY: the closure only calls `setrlimit`, which is safe to call after `fork`.
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some(bytes) = limits.memory {
+                set_limit(libc::RLIMIT_AS, bytes, bytes)?;
+            }
+            if let Some(secs) = limits.cpu {
+                // The soft limit delivers SIGXCPU; the hard one is a SIGKILL backstop.
+                set_limit(libc::RLIMIT_CPU, secs, secs + 1)?;
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Asks the process `pid` to stop. MiniZinc shuts down the solver it runs on SIGTERM.
+pub(crate) fn terminate(pid: u32) {
+    // SAFETY: `kill` has no memory-safety preconditions.
+    unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+}
+
+/// A running process whose standard output is read line by line.
+pub(crate) struct Process {
+    child: Child,
+    stdout: Option<BufReader<ChildStdout>>,
+    stderr_reader: JoinHandle<io::Result<Vec<u8>>>,
+    output_limit: Option<u64>,
+    n_bytes_read: u64,
+    output_overflow: bool,
+    read_error: Option<io::Error>,
+}
+
+impl Process {
+    /// Spawns `cmd` subject to `limits`. Resource usage of the process tree is reported by
+    /// [`Process::wait`].
+    pub(crate) fn spawn(cmd: &mut Command, limits: ResourceLimits) -> io::Result<Self> {
+        apply_limits(cmd, limits);
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Stderr is drained concurrently so that the child never blocks on a full pipe.
+        let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+        let stderr_reader = thread::spawn(move || {
+            let mut stderr = Vec::new();
+            stderr_pipe.read_to_end(&mut stderr).map(|_| stderr)
+        });
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        Ok(Process {
+            child,
+            stdout: Some(stdout),
+            stderr_reader,
+            output_limit: limits.output,
+            n_bytes_read: 0,
+            output_overflow: false,
+            read_error: None,
+        })
+    }
+
+    pub(crate) fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Reads the next line of the standard output, without the line terminator. Returns `None`
+    /// once the output is over, or once the process has been stopped for exceeding the output
+    /// limit.
+    pub(crate) fn next_line(&mut self) -> Option<String> {
+        let stdout = self.stdout.as_mut()?;
+        let mut line = Vec::new();
+        match stdout.read_until(b'\n', &mut line) {
+            Ok(0) => {}
+            Ok(n) => {
+                self.n_bytes_read += n as u64;
+                if self
+                    .output_limit
+                    .is_none_or(|limit| self.n_bytes_read <= limit)
+                {
+                    let line = String::from_utf8_lossy(&line);
+                    return Some(line.trim_end_matches(['\n', '\r']).to_string());
+                }
+                self.output_overflow = true;
+                terminate(self.id());
+            }
+            Err(e) => self.read_error = Some(e),
+        }
+        // Closing the pipe also stops anything that keeps writing to it.
+        self.stdout = None;
+        None
+    }
+
+    /// Discards the rest of the standard output and waits for the process to exit, without
+    /// reaping it. Until [`Process::wait`] is called, its pid cannot be reused, so it is still
+    /// safe to signal.
+    pub(crate) fn wait_exit(&mut self) -> io::Result<()> {
+        while self.next_line().is_some() {}
+        // SAFETY: `siginfo_t` is a plain C struct for which all-zeroes is a valid value.
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        loop {
+            // SAFETY: `info` is valid for writes for the duration of the call.
+            let res = unsafe {
+                libc::waitid(
+                    libc::P_PID,
+                    self.id() as libc::id_t,
+                    &mut info,
+                    libc::WEXITED | libc::WNOWAIT,
+                )
+            };
+            if res >= 0 {
+                return Ok(());
+            }
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+
+    /// Discards the rest of the standard output and waits for the process to exit.
+    pub(crate) fn wait(mut self) -> io::Result<ProcessOutput> {
+        while self.next_line().is_some() {}
+
+        // The child has to be reaped even if reading its output failed.
+        let (status, usage) = wait_with_usage(self.child.id())?;
+        let stderr = self.stderr_reader.join().expect("stderr reader panicked")?;
+        if let Some(e) = self.read_error {
+            return Err(e);
+        }
+        Ok(ProcessOutput {
+            status,
+            stderr,
+            usage,
+            output_overflow: self.output_overflow,
+        })
+    }
+}
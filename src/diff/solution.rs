@@ -0,0 +1,476 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+/// A piece of a solution as printed by MiniZinc: either a value (a number, a Boolean,
+/// an identifier, or a string literal) or the punctuation/whitespace between values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Value(&'a str),
+    Separator(&'a str),
+}
+
+impl<'a> Token<'a> {
+    pub(crate) fn as_str(&self) -> &'a str {
+        match self {
+            Token::Value(s) | Token::Separator(s) => s,
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+pub(crate) fn tokenize(sol: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = sol.char_indices().peekable();
+    let mut sep_start: Option<usize> = None;
+    while let Some((i, c)) = chars.next() {
+        let next_is_digit = chars.peek().is_some_and(|(_, n)| n.is_ascii_digit());
+        let value_end = if c == '"' {
+            // String literals are kept as a single value, escapes included.
+            let mut end = sol.len();
+            let mut escaped = false;
+            for (j, d) in chars.by_ref() {
+                if escaped {
+                    escaped = false;
+                } else if d == '\\' {
+                    escaped = true;
+                } else if d == '"' {
+                    end = j + 1;
+                    break;
+                }
+            }
+            Some(end)
+        } else if is_word_char(c) || (c == '-' && next_is_digit) {
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, d)) = chars.peek() {
+                if !is_word_char(d) {
+                    break;
+                }
+                end = j + d.len_utf8();
+                chars.next();
+            }
+            Some(end)
+        } else {
+            None
+        };
+
+        match value_end {
+            Some(end) => {
+                if let Some(start) = sep_start.take() {
+                    tokens.push(Token::Separator(&sol[start..i]));
+                }
+                tokens.push(Token::Value(&sol[i..end]));
+            }
+            None => {
+                sep_start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(start) = sep_start {
+        tokens.push(Token::Separator(&sol[start..]));
+    }
+    tokens
+}
+
+fn values<'a>(tokens: &[Token<'a>]) -> Vec<&'a str> {
+    tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Value(v) => Some(*v),
+            Token::Separator(_) => None,
+        })
+        .collect()
+}
+
+/// Distance between two solutions over their parsed values: the Hamming distance if both
+/// solutions have the same number of values, and the edit distance otherwise.
+fn distance(left: &[&str], right: &[&str]) -> usize {
+    if left.len() == right.len() {
+        return left.iter().zip(right).filter(|(l, r)| l != r).count();
+    }
+    let mut prev: Vec<usize> = (0..=right.len()).collect();
+    let mut cur = vec![0; right.len() + 1];
+    for (i, l) in left.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, r) in right.iter().enumerate() {
+            let subst = prev[j] + usize::from(l != r);
+            cur[j + 1] = subst.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[right.len()]
+}
+
+/// One step of a token-level alignment between two solutions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Edit<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Aligns two solutions with the same number of values value by value, along with the
+/// separators before each of them. Takes time and memory linear in their length.
+fn align_by_position<'a>(left: &[Token<'a>], right: &[Token<'a>]) -> Vec<Edit<'a>> {
+    let next_value = |tokens: &[Token], from: usize| {
+        (tokens[from..].iter())
+            .position(|t| matches!(t, Token::Value(_)))
+            .map_or(tokens.len(), |k| from + k)
+    };
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    loop {
+        let (i_value, j_value) = (next_value(left, i), next_value(right, j));
+        if left[i..i_value] == right[j..j_value] {
+            edits.extend(left[i..i_value].iter().map(|t| Edit::Same(t.as_str())));
+        } else {
+            edits.extend(left[i..i_value].iter().map(|t| Edit::Removed(t.as_str())));
+            edits.extend(right[j..j_value].iter().map(|t| Edit::Added(t.as_str())));
+        }
+        // Both sides run out of values at the same time.
+        if i_value == left.len() || j_value == right.len() {
+            return edits;
+        }
+        if left[i_value] == right[j_value] {
+            edits.push(Edit::Same(left[i_value].as_str()));
+        } else {
+            edits.push(Edit::Removed(left[i_value].as_str()));
+            edits.push(Edit::Added(right[j_value].as_str()));
+        }
+        (i, j) = (i_value + 1, j_value + 1);
+    }
+}
+
+/// Aligns two solutions token by token, similarly to `git diff --word-diff`. Values may be
+/// substituted for one another, while separators are only kept if they match exactly.
+pub(crate) fn align<'a>(removed: &'a str, added: &'a str) -> Vec<Edit<'a>> {
+    let left = tokenize(removed);
+    let right = tokenize(added);
+    // The full alignment below is quadratic, which solutions with thousands of values cannot
+    // afford. With as many values on both sides, the pairing compares them by position anyway.
+    if values(&left).len() == values(&right).len() {
+        return align_by_position(&left, &right);
+    }
+    let (n, m) = (left.len(), right.len());
+    let subst_cost = |l: &Token, r: &Token| match (l, r) {
+        _ if l == r => 0,
+        (Token::Value(_), Token::Value(_)) => 2,
+        _ => usize::MAX / 4,
+    };
+
+    // cost[i][j] is the cost of aligning the suffixes left[i..] and right[j..].
+    let mut cost = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        cost[i][m] = cost[i + 1][m] + 1;
+    }
+    for j in (0..m).rev() {
+        cost[n][j] = cost[n][j + 1] + 1;
+    }
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            cost[i][j] = (cost[i + 1][j + 1] + subst_cost(&left[i], &right[j]))
+                .min(cost[i + 1][j] + 1)
+                .min(cost[i][j + 1] + 1);
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && cost[i][j] == cost[i + 1][j + 1] + subst_cost(&left[i], &right[j]) {
+            if left[i] == right[j] {
+                edits.push(Edit::Same(left[i].as_str()));
+            } else {
+                edits.push(Edit::Removed(left[i].as_str()));
+                edits.push(Edit::Added(right[j].as_str()));
+            }
+            i += 1;
+            j += 1;
+        } else if i < n && cost[i][j] == cost[i + 1][j] + 1 {
+            edits.push(Edit::Removed(left[i].as_str()));
+            i += 1;
+        } else {
+            edits.push(Edit::Added(right[j].as_str()));
+            j += 1;
+        }
+    }
+    edits
+}
+
+/// Only this many solutions of each side are considered for pairing, so that a mismatch with
+/// thousands of differing solutions is reported without delay.
+const MAX_PAIRED: usize = 100;
+
+/// The result of matching the solutions missing on one side with those missing on the other.
+pub(crate) struct Pairing<'a> {
+    /// Removed solutions, each with its closest added solution.
+    pub(crate) pairs: Vec<(&'a str, &'a str)>,
+    /// Removed solutions that are not close to any added solution left to pair.
+    pub(crate) removed: Vec<&'a str>,
+    /// Added solutions that are not the closest match of any removed solution.
+    pub(crate) added: Vec<&'a str>,
+}
+
+/// Greedily pairs every removed solution with the closest added solution that has not been
+/// paired yet, as long as they differ in at most half of their values. Solutions further
+/// apart are shown as missing on one side and extra on the other rather than as a pair.
+pub(crate) fn pair_solutions<'a>(removed: &'a [String], added: &'a [String]) -> Pairing<'a> {
+    let added_values: Vec<Vec<&str>> = added
+        .iter()
+        .take(MAX_PAIRED)
+        .map(|s| values(&tokenize(s)))
+        .collect();
+    let mut is_paired = vec![false; added.len()];
+    let mut pairing = Pairing {
+        pairs: Vec::new(),
+        removed: Vec::new(),
+        added: Vec::new(),
+    };
+
+    for (n, sol) in removed.iter().enumerate() {
+        if n >= MAX_PAIRED {
+            pairing.removed.push(sol.as_str());
+            continue;
+        }
+        let sol_values = values(&tokenize(sol));
+        let closest = added_values
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !is_paired[*i])
+            .map(|(i, v)| (i, distance(&sol_values, v), sol_values.len().max(v.len())))
+            .filter(|(_, distance, n_values)| 2 * distance <= *n_values)
+            .min_by_key(|(_, distance, _)| *distance)
+            .map(|(i, _, _)| i);
+        match closest {
+            Some(i) => {
+                is_paired[i] = true;
+                pairing.pairs.push((sol.as_str(), added[i].as_str()));
+            }
+            None => pairing.removed.push(sol.as_str()),
+        }
+    }
+    pairing.added = added
+        .iter()
+        .zip(is_paired)
+        .filter(|(_, paired)| !paired)
+        .map(|(sol, _)| sol.as_str())
+        .collect();
+    pairing
+}
+
+/// Names every value of a solution after the variable it belongs to, e.g. `x=[3, 4]` yields
+/// `("x[1]", "3")` and `("x[2]", "4")`. Values that do not follow a `name =` prefix are named
+/// by their position among such values.
+pub(crate) fn assignments(sol: &str) -> Vec<(String, &str)> {
+    let tokens = tokenize(sol);
+    let mut result = Vec::new();
+    let mut name: Option<&str> = None;
+    let mut indices: Vec<usize> = Vec::new();
+    let mut n_unnamed = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Value(v) => {
+                let is_name = matches!(
+                    tokens.get(i + 1),
+                    Some(Token::Separator(s)) if s.trim_start().starts_with('=')
+                );
+                if is_name {
+                    name = Some(v);
+                    indices.clear();
+                    continue;
+                }
+                if let Some(top) = indices.last_mut() {
+                    *top += 1;
+                }
+                let prefix = match name {
+                    Some(name) => name.to_string(),
+                    None if indices.is_empty() => {
+                        n_unnamed += 1;
+                        format!("#{n_unnamed}")
+                    }
+                    None => String::new(),
+                };
+                let label = if indices.is_empty() {
+                    prefix
+                } else {
+                    let idx: Vec<String> = indices.iter().map(usize::to_string).collect();
+                    format!("{prefix}[{}]", idx.join(","))
+                };
+                result.push((label, *v));
+            }
+            Token::Separator(s) => {
+                for c in s.chars() {
+                    match c {
+                        '[' | '{' | '(' => indices.push(0),
+                        ']' | '}' | ')' => {
+                            indices.pop();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+/// A variable whose value is noticeably more common among the solutions found by only one
+/// solver than among all solutions found by the other one.
+pub(crate) struct Deviation {
+    pub(crate) variable: String,
+    pub(crate) value: String,
+    /// Share of the differing solutions where `variable` takes `value`.
+    pub(crate) share: f64,
+    /// Share of the other solver's solutions where `variable` takes `value`.
+    pub(crate) reference_share: f64,
+}
+
+fn value_counts<S: AsRef<str>>(
+    solutions: impl IntoIterator<Item = S>,
+) -> (HashMap<(String, String), usize>, usize) {
+    let mut counts = HashMap::new();
+    let mut total = 0;
+    for sol in solutions {
+        total += 1;
+        for (variable, value) in assignments(sol.as_ref()) {
+            *counts.entry((variable, value.to_string())).or_insert(0) += 1;
+        }
+    }
+    (counts, total)
+}
+
+/// Finds the variable values that set the differing solutions apart from the `reference`
+/// solutions, most significant first.
+pub(crate) fn deviations<S: AsRef<str>, R: AsRef<str>>(
+    differing: impl IntoIterator<Item = S>,
+    reference: impl IntoIterator<Item = R>,
+) -> Vec<Deviation> {
+    let (counts, total) = value_counts(differing);
+    let (reference_counts, reference_total) = value_counts(reference);
+    if total == 0 {
+        return Vec::new();
+    }
+
+    // Only keep the most common value of each variable.
+    let mut most_common: HashMap<String, (String, usize)> = HashMap::new();
+    for ((variable, value), count) in counts {
+        let entry = most_common
+            .entry(variable)
+            .or_insert_with(|| (value.clone(), 0));
+        if count > entry.1 || (count == entry.1 && value < entry.0) {
+            *entry = (value, count);
+        }
+    }
+
+    let mut result: Vec<Deviation> = most_common
+        .into_iter()
+        .map(|(variable, (value, count))| {
+            let reference_count = reference_counts
+                .get(&(variable.clone(), value.clone()))
+                .copied()
+                .unwrap_or(0);
+            Deviation {
+                share: count as f64 / total as f64,
+                reference_share: if reference_total == 0 {
+                    0.0
+                } else {
+                    reference_count as f64 / reference_total as f64
+                },
+                variable,
+                value,
+            }
+        })
+        .filter(|d| d.share > d.reference_share)
+        .collect();
+    result.sort_by(|a, b| {
+        (b.share - b.reference_share)
+            .total_cmp(&(a.share - a.reference_share))
+            .then_with(|| a.variable.cmp(&b.variable))
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(solutions: &[&str]) -> Vec<String> {
+        solutions.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn pairs_close_solutions() {
+        let removed = strings(&["x = [1, 2, 3];", "x = [7, 8, 9];"]);
+        let added = strings(&["x = [7, 8, 0];", "x = [1, 2, 4];"]);
+        let pairing = pair_solutions(&removed, &added);
+        assert_eq!(
+            pairing.pairs,
+            vec![
+                ("x = [1, 2, 3];", "x = [1, 2, 4];"),
+                ("x = [7, 8, 9];", "x = [7, 8, 0];")
+            ]
+        );
+        assert!(pairing.removed.is_empty());
+        assert!(pairing.added.is_empty());
+    }
+
+    #[test]
+    fn aligns_solutions_with_as_many_values_by_position() {
+        assert_eq!(
+            align("x = [1, 2, 3];", "x = [1, 5, 3];"),
+            vec![
+                Edit::Same("x"),
+                Edit::Same(" = ["),
+                Edit::Same("1"),
+                Edit::Same(", "),
+                Edit::Removed("2"),
+                Edit::Added("5"),
+                Edit::Same(", "),
+                Edit::Same("3"),
+                Edit::Same("];"),
+            ]
+        );
+    }
+
+    #[test]
+    fn aligns_solutions_with_different_numbers_of_values() {
+        assert_eq!(
+            align("x=[1]", "x=[1, 2]"),
+            vec![
+                Edit::Same("x"),
+                Edit::Same("=["),
+                Edit::Same("1"),
+                Edit::Added(", "),
+                Edit::Added("2"),
+                Edit::Same("]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn does_not_pair_distant_solutions() {
+        let removed = strings(&["x = [1, 2, 3];"]);
+        let added = strings(&["x = [4, 5, 6];"]);
+        let pairing = pair_solutions(&removed, &added);
+        assert!(pairing.pairs.is_empty());
+        assert_eq!(pairing.removed, vec!["x = [1, 2, 3];"]);
+        assert_eq!(pairing.added, vec!["x = [4, 5, 6];"]);
+    }
+}
@@ -0,0 +1,247 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    fs::{self, File, OpenOptions},
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    os::unix::fs::FileExt,
+    sync::{
+        OnceLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+pub(crate) type Fingerprint = u128;
+
+/// Spilled solutions are written to disk in chunks of this many bytes.
+const SPILL_BUFFER_SIZE: usize = 1 << 20;
+
+pub(crate) fn fingerprint(solution: &str) -> Fingerprint {
+    let half = |seed: u8| {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        solution.hash(&mut hasher);
+        hasher.finish()
+    };
+    (Fingerprint::from(half(0)) << 64) | Fingerprint::from(half(1))
+}
+
+/// Solutions stored by fingerprint only, with their text spilled to an anonymous temporary
+/// file. The text is read back for the few solutions that need to be displayed.
+pub(crate) struct FingerprintSet {
+    /// Offset and length of the text of each solution in the spill file
+    index: HashMap<Fingerprint, (u64, usize)>,
+    spill: File,
+    /// Number of bytes already written to the spill file
+    n_flushed: u64,
+    buffer: Vec<u8>,
+}
+
+impl FingerprintSet {
+    fn new() -> io::Result<Self> {
+        static N_SPILL_FILES: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "minizinc-diff-{}-{}.solutions",
+            std::process::id(),
+            N_SPILL_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let spill = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        // The file stays accessible through `spill` and is gone once it is closed.
+        fs::remove_file(&path)?;
+        Ok(FingerprintSet {
+            index: HashMap::new(),
+            spill,
+            n_flushed: 0,
+            buffer: Vec::new(),
+        })
+    }
+
+    fn insert(&mut self, solution: &str) -> io::Result<()> {
+        let offset = self.n_flushed + self.buffer.len() as u64;
+        if let Entry::Vacant(entry) = self.index.entry(fingerprint(solution)) {
+            entry.insert((offset, solution.len()));
+            self.buffer.extend_from_slice(solution.as_bytes());
+        }
+        if self.buffer.len() >= SPILL_BUFFER_SIZE {
+            self.spill.write_all_at(&self.buffer, self.n_flushed)?;
+            self.n_flushed += self.buffer.len() as u64;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+
+    /// Reads back the text of a solution. A solution that cannot be read back is replaced by
+    /// a note, so that the rest of the report is still shown.
+    fn text(&self, fp: &Fingerprint) -> String {
+        let (offset, len) = self.index[fp];
+        let mut bytes = vec![0; len];
+        if offset >= self.n_flushed {
+            let start = (offset - self.n_flushed) as usize;
+            bytes.copy_from_slice(&self.buffer[start..start + len]);
+        } else if let Err(e) = self.spill.read_exact_at(&mut bytes, offset) {
+            return format!("<solution {fp:032x} could not be read back: {e}>");
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+/// The set of distinct solutions found by a solver.
+pub(crate) enum SolutionSet {
+    InMemory {
+        solutions: HashSet<String>,
+        /// Fingerprints of the solutions, computed on the first lookup by fingerprint
+        fingerprints: OnceLock<HashSet<Fingerprint>>,
+    },
+    Fingerprinted(FingerprintSet),
+}
+
+impl Default for SolutionSet {
+    fn default() -> Self {
+        SolutionSet::InMemory {
+            solutions: HashSet::new(),
+            fingerprints: OnceLock::new(),
+        }
+    }
+}
+
+impl SolutionSet {
+    /// Creates an empty set, which stores only fingerprints if `fingerprinted` is set.
+    pub(crate) fn new(fingerprinted: bool) -> io::Result<Self> {
+        Ok(if fingerprinted {
+            SolutionSet::Fingerprinted(FingerprintSet::new()?)
+        } else {
+            SolutionSet::default()
+        })
+    }
+
+    pub(crate) fn insert(&mut self, solution: String) -> io::Result<()> {
+        match self {
+            SolutionSet::InMemory {
+                solutions,
+                fingerprints,
+            } => {
+                if let Some(fingerprints) = fingerprints.get_mut() {
+                    fingerprints.insert(fingerprint(&solution));
+                }
+                solutions.insert(solution);
+                Ok(())
+            }
+            SolutionSet::Fingerprinted(set) => set.insert(&solution),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            SolutionSet::InMemory { solutions, .. } => solutions.len(),
+            SolutionSet::Fingerprinted(set) => set.index.len(),
+        }
+    }
+
+    fn contains_fingerprint(&self, fp: &Fingerprint) -> bool {
+        match self {
+            SolutionSet::InMemory {
+                solutions,
+                fingerprints,
+            } => fingerprints
+                .get_or_init(|| solutions.iter().map(|sol| fingerprint(sol)).collect())
+                .contains(fp),
+            SolutionSet::Fingerprinted(set) => set.index.contains_key(fp),
+        }
+    }
+
+    pub(crate) fn contains(&self, solution: &str) -> bool {
+        match self {
+            SolutionSet::InMemory { solutions, .. } => solutions.contains(solution),
+            SolutionSet::Fingerprinted(set) => set.index.contains_key(&fingerprint(solution)),
+        }
+    }
+
+    /// Solutions of `self` that are missing from `other`. With fingerprints, only the text of
+    /// these solutions is read back.
+    pub(crate) fn difference(&self, other: &SolutionSet) -> Vec<String> {
+        match self {
+            SolutionSet::InMemory { solutions, .. } => solutions
+                .iter()
+                .filter(|sol| !other.contains(sol))
+                .cloned()
+                .collect(),
+            SolutionSet::Fingerprinted(set) => set
+                .index
+                .keys()
+                .filter(|fp| !other.contains_fingerprint(fp))
+                .map(|fp| set.text(fp))
+                .collect(),
+        }
+    }
+
+    /// Texts of all solutions, read back one at a time with fingerprints.
+    pub(crate) fn texts(&self) -> Box<dyn Iterator<Item = String> + '_> {
+        match self {
+            SolutionSet::InMemory { solutions, .. } => Box::new(solutions.iter().cloned()),
+            SolutionSet::Fingerprinted(set) => Box::new(set.index.keys().map(|fp| set.text(fp))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_back_solutions_across_a_buffer_flush() {
+        let mut set = FingerprintSet::new().unwrap();
+        let large = |c: char| c.to_string().repeat(SPILL_BUFFER_SIZE / 2 + 1);
+        let solutions = [large('a'), large('b'), "x = 1;".to_string()];
+        for solution in &solutions {
+            set.insert(solution).unwrap();
+        }
+        // The first two solutions fill the buffer, which is then written to the spill file.
+        assert_eq!(set.n_flushed as usize, 2 * solutions[0].len());
+        for solution in &solutions {
+            assert_eq!(set.text(&fingerprint(solution)), *solution);
+        }
+    }
+
+    #[test]
+    fn ignores_duplicate_solutions() {
+        let mut set = SolutionSet::new(true).unwrap();
+        set.insert("x = 1;".to_string()).unwrap();
+        set.insert("x = 1;".to_string()).unwrap();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn compares_fingerprinted_and_in_memory_sets() {
+        let mut fingerprinted = SolutionSet::new(true).unwrap();
+        let mut in_memory = SolutionSet::new(false).unwrap();
+        for solution in ["x = 1;", "x = 2;"] {
+            fingerprinted.insert(solution.to_string()).unwrap();
+        }
+        for solution in ["x = 2;", "x = 3;"] {
+            in_memory.insert(solution.to_string()).unwrap();
+        }
+        assert_eq!(fingerprinted.difference(&in_memory), ["x = 1;"]);
+        assert_eq!(in_memory.difference(&fingerprinted), ["x = 3;"]);
+        // Solutions inserted after the fingerprints were computed are still found.
+        in_memory.insert("x = 1;".to_string()).unwrap();
+        assert!(fingerprinted.difference(&in_memory).is_empty());
+    }
+}
@@ -56,7 +56,7 @@ fn parse_instance_shape(s: &str) -> Result<InstanceShape, String> {
             let mut n_constraints = 2;
             let mut n_variables = 10;
             let mut coef_range = 100;
-            if let Some(n_runs_str) = slice.get(0) {
+            if let Some(n_runs_str) = slice.first() {
                 if let Ok(n_runs) = n_runs_str.parse() {
                     n_samples = n_runs;
                 } else {
@@ -0,0 +1,34 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::path::Path;
+
+use crate::diff::display::OutputMode;
+
+/// Points to the redefinitions of the solver as the likely cause of a mismatch.
+pub(crate) fn report_suspects(tag: &str, dir: &Path, output: OutputMode) {
+    match output {
+        OutputMode::Ascii => println!(
+            "[GLOBALS] The solutions depend on the globals library: the redefinitions of {tag} for the predicates listed above are the first suspects (FlatZinc in {}).",
+            dir.display()
+        ),
+        OutputMode::Rich => println!(
+            "\x1b[36m🔎 The solutions depend on the globals library: the redefinitions of \x1b[1m{tag}\x1b[0m\x1b[36m for the predicates listed above are the first suspects\x1b[0m (FlatZinc in \x1b[1m{}\x1b[0m).",
+            dir.display()
+        ),
+        OutputMode::None => {}
+    }
+}
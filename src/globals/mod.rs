@@ -0,0 +1,119 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+mod display;
+
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
+use clap::Args;
+
+use crate::{
+    compiler::compare_flatzinc,
+    diff::{
+        RunOptions, SolverSpec, check_solver,
+        display::{
+            OutputMode, ReportOptions, SolverErrorType, print_diff, report_divergence,
+            report_failure, report_minizinc,
+        },
+        flatzinc, parse_solver_spec, run_pair, single_solution_solvers,
+    },
+    globals::display::report_suspects,
+};
+
+#[derive(Args, Debug)]
+pub(crate) struct GlobalsDiffArgs {
+    /// MiniZinc model file
+    model: PathBuf,
+    /// MiniZinc data file
+    instance: PathBuf,
+    /// Solver tag run with both globals libraries, followed by --flags if needed
+    #[arg(value_parser = parse_solver_spec)]
+    solver: SolverSpec,
+    /// Globals library of the second run (displayed on the right), passed to `-G`
+    #[arg(short = 'G', long, default_value = "std")]
+    globals: String,
+    /// Timeout for both runs
+    #[arg(short, long)]
+    timeout_secs: Option<u64>,
+    /// Disables all output
+    #[arg(short, long)]
+    quiet: bool,
+    /// `minizinc` executable used unless the spec gives one with `tag@path`
+    #[arg(long, value_name = "PATH")]
+    minizinc: Option<PathBuf>,
+    /// Solution checker model (.mzc); both solvers are stopped once it rejects a solution
+    #[arg(long, value_name = "FILE")]
+    checker: Option<PathBuf>,
+    /// Directory where the compiled FlatZinc is kept; by default a new temporary directory
+    #[arg(long, value_name = "DIR")]
+    fzn_dir: Option<PathBuf>,
+    /// Logs the command line, output, exit status and timestamps of every run to this directory
+    #[arg(long, value_name = "DIR")]
+    log_dir: Option<PathBuf>,
+    /// Prints the command of every run, with the MiniZinc environment variables, to stderr
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+pub(crate) fn run(mut args: GlobalsDiffArgs) -> ExitCode {
+    args.solver.default_minizinc(args.minizinc.as_deref());
+    let output_mode = OutputMode::new(args.quiet);
+    // Both runs use the same solver, so it is checked once, before `-G` is added.
+    if let Err(e) = check_solver(&mut args.solver, SolverErrorType::Both, output_mode) {
+        return report_failure(e, SolverErrorType::Both, output_mode).into();
+    }
+    let own = args.solver.clone();
+    let mut standard = args.solver.clone();
+    standard
+        .flags
+        .extend(["-G".to_string(), args.globals.clone()]);
+    report_minizinc(&own, &standard, output_mode);
+
+    // The FlatZinc shows which globals the solver keeps as native constraints.
+    let dir = args.fzn_dir.clone().unwrap_or_else(flatzinc::default_dir);
+    let own_label = format!("{} globals", own.tag);
+    let standard_label = format!("{} globals", args.globals);
+    compare_flatzinc(
+        &args.model,
+        &args.instance,
+        (&own, &standard),
+        (&own_label, &standard_label),
+        &dir,
+        args.verbose,
+        output_mode,
+    );
+
+    let options = RunOptions {
+        timeout: args.timeout_secs.map(Duration::from_secs),
+        checker: args.checker,
+        log_dir: args.log_dir,
+        verbose: args.verbose,
+        ..RunOptions::default()
+    };
+    let report_options = ReportOptions {
+        single_solution: single_solution_solvers(&own, &standard),
+        ..ReportOptions::default()
+    };
+    let (res1, res2, divergence) =
+        run_pair(&args.model, &args.instance, own, standard, &options, false);
+    let status = match divergence {
+        Some(divergence) => report_divergence(divergence, output_mode),
+        None => print_diff(&res1, &res2, output_mode, &report_options),
+    };
+    if status.verdict.is_some() {
+        report_suspects(&args.solver.tag, &dir, output_mode);
+    }
+    status.into()
+}
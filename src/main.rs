@@ -13,9 +13,16 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
+mod campaign;
+mod compiler;
 mod diff;
 mod generate;
+mod globals;
 pub(crate) mod hhmmss;
+mod optimisation;
+mod perf;
+mod report;
+mod solvers;
 
 use clap::{Parser, Subcommand};
 use std::process::ExitCode;
@@ -33,6 +40,20 @@ enum Commands {
     Generate(generate::GenArgs),
     /// Test a single model-instance pair with two solvers
     Diff(diff::DiffArgs),
+    /// Check every generated instance with two solvers and bucket the findings
+    Campaign(campaign::CampaignArgs),
+    /// Run one solver through two MiniZinc installations and compare the solutions and the FlatZinc
+    CompilerDiff(compiler::CompilerDiffArgs),
+    /// Run one solver with its own globals library and with the standard one, and compare the solutions
+    GlobalsDiff(globals::GlobalsDiffArgs),
+    /// Run one solver with several compiler configurations and compare the solutions with the default one
+    OptDiff(optimisation::OptDiffArgs),
+    /// Compare the running times of two solvers over repeated runs
+    Perf(perf::PerfArgs),
+    /// List the solvers known to MiniZinc with their versions and supported flags
+    Solvers(solvers::SolversArgs),
+    /// Write a markdown issue draft for a finding saved with --save-findings
+    ReportBug(report::ReportBugArgs),
 }
 
 fn main() -> ExitCode {
@@ -41,5 +62,12 @@ fn main() -> ExitCode {
     match cli.cmd {
         Commands::Generate(args) => generate::run(args),
         Commands::Diff(args) => diff::run(args),
+        Commands::Perf(args) => perf::run(args),
+        Commands::Campaign(args) => campaign::run(args),
+        Commands::CompilerDiff(args) => compiler::run(args),
+        Commands::GlobalsDiff(args) => globals::run(args),
+        Commands::OptDiff(args) => optimisation::run(args),
+        Commands::ReportBug(args) => report::run(args),
+        Commands::Solvers(args) => solvers::run(args),
     }
 }
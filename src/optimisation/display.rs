@@ -0,0 +1,150 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::diff::{
+    FailureKind, SolverOutput, SolverSpec,
+    display::{CheckStatus, Outcome, OutputMode, Verdict},
+};
+
+/// How the solutions of one compiler configuration compare with the default one.
+pub(crate) struct ConfigResult {
+    /// Compiler options of the configuration
+    pub(crate) config: String,
+    pub(crate) n_solutions: usize,
+    pub(crate) status: CheckStatus,
+    /// Description of the failure of the run, if any
+    pub(crate) error: Option<String>,
+}
+
+impl ConfigResult {
+    fn crashed(&self) -> bool {
+        self.status.right == Outcome::Failed(FailureKind::Crash)
+    }
+
+    /// Whether the solutions differ or the run crashed, either of which is a bug.
+    pub(crate) fn is_finding(&self) -> bool {
+        self.status.verdict.is_some() || self.crashed()
+    }
+
+    fn outcome(&self) -> String {
+        let outcome = self.status.right.describe();
+        match &self.error {
+            Some(error) => format!("{outcome} ({error})"),
+            None => outcome.to_string(),
+        }
+    }
+}
+
+pub(crate) fn print_baseline(solver: &SolverSpec, baseline: &SolverOutput, output: OutputMode) {
+    let run = baseline.run();
+    let finished = if matches!(baseline, SolverOutput::Complete(_)) {
+        ""
+    } else {
+        ", before timing out"
+    };
+    match output {
+        OutputMode::Ascii => println!(
+            "[BASELINE] {}: {} solutions in {:.3}s{finished}",
+            solver.describe(),
+            run.solutions.len(),
+            run.duration.as_secs_f64()
+        ),
+        OutputMode::Rich => println!(
+            "📐 Baseline \x1b[1m{}\x1b[0m: {} solutions in {:.3}s{finished}",
+            solver.describe(),
+            run.solutions.len(),
+            run.duration.as_secs_f64()
+        ),
+        OutputMode::None => {}
+    }
+}
+
+pub(crate) fn print_config(output: OutputMode, (n, total): (usize, usize), result: &ConfigResult) {
+    let config = &result.config;
+    let n_solutions = result.n_solutions;
+    match (output, result.status.verdict) {
+        (OutputMode::None, _) => {}
+        (OutputMode::Ascii, Some(Verdict::Rejected(_))) => println!(
+            "[{n}/{total}] [FAIL] {config}: a solution was rejected by the checker, {n_solutions} found"
+        ),
+        (OutputMode::Rich, Some(Verdict::Rejected(_))) => println!(
+            "[{n}/{total}] \x1b[31m❌ \x1b[1m{config}\x1b[0m\x1b[31m: a solution was rejected by the checker, {n_solutions} found\x1b[0m"
+        ),
+        (OutputMode::Ascii, Some(verdict)) => println!(
+            "[{n}/{total}] [FAIL] {config}: different solutions ({}), {n_solutions} found",
+            verdict.describe()
+        ),
+        (OutputMode::Rich, Some(verdict)) => println!(
+            "[{n}/{total}] \x1b[31m❌ \x1b[1m{config}\x1b[0m\x1b[31m: different solutions ({}), {n_solutions} found\x1b[0m",
+            verdict.describe()
+        ),
+        (OutputMode::Ascii, None) if result.crashed() => {
+            println!("[{n}/{total}] [FAIL] {config}: {}", result.outcome())
+        }
+        (OutputMode::Rich, None) if result.crashed() => println!(
+            "[{n}/{total}] \x1b[31m💥 \x1b[1m{config}\x1b[0m\x1b[31m: {}\x1b[0m",
+            result.outcome()
+        ),
+        (OutputMode::Ascii, None) if result.status.right == Outcome::Complete => {
+            println!("[{n}/{total}] [OK] {config}: same {n_solutions} solutions")
+        }
+        (OutputMode::Rich, None) if result.status.right == Outcome::Complete => println!(
+            "[{n}/{total}] \x1b[32m✅ \x1b[1m{config}\x1b[0m\x1b[32m: same {n_solutions} solutions\x1b[0m"
+        ),
+        (OutputMode::Ascii, None) => println!(
+            "[{n}/{total}] [INCONCLUSIVE] {config}: {}, {n_solutions} solutions checked",
+            result.outcome()
+        ),
+        (OutputMode::Rich, None) => println!(
+            "[{n}/{total}] \x1b[33m⏳ \x1b[1m{config}\x1b[0m\x1b[33m: {}, {n_solutions} solutions checked\x1b[0m",
+            result.outcome()
+        ),
+    }
+}
+
+pub(crate) fn print_summary(output: OutputMode, results: &[ConfigResult]) {
+    let findings: Vec<&str> = results
+        .iter()
+        .filter(|r| r.is_finding())
+        .map(|r| r.config.as_str())
+        .collect();
+    let list = findings
+        .iter()
+        .map(|config| format!("`{config}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    match output {
+        OutputMode::Ascii if findings.is_empty() => println!(
+            "[DONE] No configuration out of {} changes the solutions.",
+            results.len()
+        ),
+        OutputMode::Ascii => println!(
+            "[DONE] {} configurations out of {} change the solutions or crash: {list}",
+            findings.len(),
+            results.len()
+        ),
+        OutputMode::Rich if findings.is_empty() => println!(
+            "\x1b[1m🏁 No configuration out of {} changes the solutions.\x1b[0m",
+            results.len()
+        ),
+        OutputMode::Rich => println!(
+            "\x1b[1m🏁 \x1b[31m{}\x1b[0m\x1b[1m configurations out of {} change the solutions or crash:\x1b[0m {list}",
+            findings.len(),
+            results.len()
+        ),
+        OutputMode::None => {}
+    }
+}
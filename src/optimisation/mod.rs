@@ -0,0 +1,152 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+mod display;
+
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
+use clap::Args;
+
+use crate::{
+    diff::{
+        RunOptions, Side, SolverOutput, SolverSpec, check_solver,
+        display::{
+            CheckStatus, OutputMode, ReportOptions, SolverErrorType, check_rejection, print_diff,
+            report_failure,
+        },
+        minizinc::run_solver,
+        parse_solver_spec, single_solution_solvers,
+    },
+    optimisation::display::{ConfigResult, print_baseline, print_config, print_summary},
+};
+
+/// Compiler options that must not change the solutions. Those needing Gecode presolving,
+/// `-O5` and `--use-gecode`, are left out, as they crash on installations without it.
+const DEFAULT_CONFIGS: [&str; 7] = [
+    "-O0",
+    "-O2",
+    "-O3",
+    "-O4",
+    "--no-optimize",
+    "--two-pass",
+    "-O3 --two-pass",
+];
+
+#[derive(Args, Debug)]
+pub(crate) struct OptDiffArgs {
+    /// MiniZinc model file
+    model: PathBuf,
+    /// MiniZinc data file
+    instance: PathBuf,
+    /// Solver tag run with every configuration, followed by --flags if needed
+    #[arg(value_parser = parse_solver_spec)]
+    solver: SolverSpec,
+    /// Compiler options of a configuration compared with the default one; can be repeated
+    #[arg(long = "config", value_name = "FLAGS", allow_hyphen_values = true, default_values = DEFAULT_CONFIGS)]
+    configs: Vec<String>,
+    /// Timeout for every run
+    #[arg(short, long)]
+    timeout_secs: Option<u64>,
+    /// Disables all output
+    #[arg(short, long)]
+    quiet: bool,
+    /// `minizinc` executable used unless the spec gives one with `tag@path`
+    #[arg(long, value_name = "PATH")]
+    minizinc: Option<PathBuf>,
+    /// Solution checker model (.mzc) verifying the solutions of every run
+    #[arg(long, value_name = "FILE")]
+    checker: Option<PathBuf>,
+    /// Logs the command line, output, exit status and timestamps of every run to this directory
+    #[arg(long, value_name = "DIR")]
+    log_dir: Option<PathBuf>,
+    /// Prints the command of every run, with the MiniZinc environment variables, to stderr
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+pub(crate) fn run(mut args: OptDiffArgs) -> ExitCode {
+    args.solver.default_minizinc(args.minizinc.as_deref());
+    let output_mode = OutputMode::new(args.quiet);
+    // The baseline is the left run, and a solver that cannot be run fails it first.
+    if let Err(e) = check_solver(&mut args.solver, SolverErrorType::Both, output_mode) {
+        return report_failure(e, SolverErrorType::Left, output_mode).into();
+    }
+    let report_options = ReportOptions {
+        single_solution: single_solution_solvers(&args.solver, &args.solver),
+        ..ReportOptions::default()
+    };
+    let options = RunOptions {
+        timeout: args.timeout_secs.map(Duration::from_secs),
+        checker: args.checker.clone(),
+        log_dir: args.log_dir.clone(),
+        verbose: args.verbose,
+        ..RunOptions::default()
+    };
+    let run = |solver: &SolverSpec| {
+        run_solver(
+            args.model.clone(),
+            args.instance.clone(),
+            solver.clone(),
+            &options,
+        )
+        .unwrap_or_else(SolverOutput::from)
+    };
+
+    let baseline = run(&args.solver);
+    if let SolverOutput::Failed(e, _) = baseline {
+        return report_failure(e, SolverErrorType::Left, output_mode).into();
+    }
+    // Configurations cannot be compared against solutions that are wrong to begin with.
+    if let Some(verdict) = check_rejection(baseline.run(), Side::Left, output_mode) {
+        return CheckStatus {
+            left: baseline.outcome(),
+            ..CheckStatus::diff(verdict)
+        }
+        .into();
+    }
+    print_baseline(&args.solver, &baseline, output_mode);
+
+    let mut results = Vec::new();
+    for (i, config) in args.configs.iter().enumerate() {
+        let flags = match shell_words::split(config) {
+            Ok(flags) => flags,
+            Err(e) => {
+                eprintln!("Failed to parse the configuration `{config}`: {e}");
+                return ExitCode::FAILURE;
+            }
+        };
+        let mut solver = args.solver.clone();
+        solver.flags.extend(flags);
+        let output = run(&solver);
+        let status = print_diff(&baseline, &output, OutputMode::None, &report_options);
+        let result = ConfigResult {
+            config: config.clone(),
+            n_solutions: output.run().solutions.len(),
+            status,
+            error: match &output {
+                SolverOutput::Failed(e, _) => e.describe(),
+                _ => None,
+            },
+        };
+        print_config(output_mode, (i + 1, args.configs.len()), &result);
+        results.push(result);
+    }
+    print_summary(output_mode, &results);
+    // The baseline is the left run and the configuration the right one.
+    match results.iter().find(|result| result.is_finding()) {
+        Some(result) => result.status.into(),
+        None => ExitCode::SUCCESS,
+    }
+}
@@ -0,0 +1,160 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{
+    diff::display::OutputMode,
+    perf::{Metric, stats::Estimate},
+};
+
+/// Fewest runs of each solver from which a difference is judged. With fewer, the bootstrap
+/// interval collapses and a single noisy run would look significant.
+pub(crate) const MIN_RUNS: usize = 3;
+
+/// How the right solver compares with the left one, with the ratio of their medians.
+pub(crate) enum PerfVerdict {
+    /// The right solver is significantly faster (or lighter).
+    Speedup(f64),
+    /// The difference is not statistically significant.
+    NoChange,
+    /// The right solver is significantly slower (or heavier), but within the tolerated threshold.
+    Slowdown(f64),
+    /// The right solver is significantly slower (or heavier) than the tolerated threshold.
+    Regression(f64),
+    /// There are too few runs, or the ratio is undefined as the left median is zero.
+    Inconclusive,
+}
+
+pub(crate) struct PerfReport {
+    pub(crate) left: Estimate,
+    pub(crate) right: Estimate,
+    /// Ratio of the right median to the left median, unless the left median is zero.
+    pub(crate) factor: Option<Estimate>,
+    pub(crate) metric: Metric,
+    pub(crate) n_runs: usize,
+    pub(crate) confidence: f64,
+    pub(crate) max_slowdown: f64,
+}
+
+impl PerfReport {
+    pub(crate) fn verdict(&self) -> PerfVerdict {
+        let Some(factor) = self.factor.filter(|_| self.n_runs >= MIN_RUNS) else {
+            return PerfVerdict::Inconclusive;
+        };
+        if factor.low > self.max_slowdown {
+            PerfVerdict::Regression(factor.value)
+        } else if factor.low > 1.0 {
+            PerfVerdict::Slowdown(factor.value)
+        } else if factor.high < 1.0 {
+            PerfVerdict::Speedup(factor.value)
+        } else {
+            PerfVerdict::NoChange
+        }
+    }
+
+    /// Why no verdict can be given, if that is the case.
+    fn inconclusive_reason(&self) -> String {
+        if self.n_runs < MIN_RUNS {
+            format!("Too few runs to judge significance; use --runs {MIN_RUNS} or more.")
+        } else {
+            "The left median is zero, so no slowdown factor can be given.".to_string()
+        }
+    }
+}
+
+fn print_perf_ascii(report: &PerfReport) {
+    let pct = 100.0 * report.confidence;
+    let (metric, fmt) = (report.metric.name(), |v| report.metric.format(v));
+    let (worse, better) = report.metric.comparatives();
+    for (side, est) in [("Left", report.left), ("Right", report.right)] {
+        println!(
+            "[PERF] {side} median {metric}: {} ({pct:.0}% CI {}..{}, {} runs)",
+            fmt(est.value),
+            fmt(est.low),
+            fmt(est.high),
+            report.n_runs
+        );
+    }
+    match report.factor {
+        Some(f) => println!(
+            "[PERF] Right/left {metric} ratio: {:.3}x ({pct:.0}% CI {:.3}x..{:.3}x)",
+            f.value, f.low, f.high
+        ),
+        None => println!("[PERF] Right/left {metric} ratio undefined, as the left median is zero"),
+    }
+    match report.verdict() {
+        PerfVerdict::Speedup(f) => println!(
+            "[OK] Right solver is {better} by a factor of {:.3}.",
+            1.0 / f
+        ),
+        PerfVerdict::NoChange => println!("[OK] No significant difference."),
+        PerfVerdict::Slowdown(f) => println!(
+            "[OK] Right solver is {worse} by a factor of {f:.3}, within the threshold of {:.3}.",
+            report.max_slowdown
+        ),
+        PerfVerdict::Regression(f) => println!(
+            "[FAIL] Right solver is {worse} by a factor of {f:.3}, beyond the threshold of {:.3}.",
+            report.max_slowdown
+        ),
+        PerfVerdict::Inconclusive => {
+            println!("[INCONCLUSIVE] {}", report.inconclusive_reason())
+        }
+    }
+}
+
+fn print_perf_rich(report: &PerfReport) {
+    let pct = 100.0 * report.confidence;
+    let (metric, fmt) = (report.metric.name(), |v| report.metric.format(v));
+    let (worse, better) = report.metric.comparatives();
+    for (side, est) in [("left", report.left), ("right", report.right)] {
+        println!(
+            "⌛ Median {metric} of {side} runs: \x1b[1m{}\x1b[0m ({pct:.0}% CI {}..{}, {} runs)",
+            fmt(est.value),
+            fmt(est.low),
+            fmt(est.high),
+            report.n_runs
+        );
+    }
+    match report.factor {
+        Some(f) => println!(
+            "📏 Right/left {metric} ratio: \x1b[1m{:.3}x\x1b[0m ({pct:.0}% CI {:.3}x..{:.3}x)",
+            f.value, f.low, f.high
+        ),
+        None => println!("📏 Right/left {metric} ratio undefined, as the left median is zero"),
+    }
+    match report.verdict() {
+        PerfVerdict::Speedup(f) => println!(
+            "\x1b[32m🚀 Right solver is \x1b[1m{better}\x1b[0m\x1b[32m by a factor of {:.3}.\x1b[0m",
+            1.0 / f
+        ),
+        PerfVerdict::NoChange => println!("\x1b[32m✅ No significant difference.\x1b[0m"),
+        PerfVerdict::Slowdown(f) => println!(
+            "\x1b[33m🐢 Right solver is {worse} by a factor of {f:.3}, within the threshold of {:.3}.\x1b[0m",
+            report.max_slowdown
+        ),
+        PerfVerdict::Regression(f) => println!(
+            "\x1b[31m❌ Right solver is \x1b[1m{worse}\x1b[0m\x1b[31m by a factor of {f:.3}, beyond the threshold of {:.3}.\x1b[0m",
+            report.max_slowdown
+        ),
+        PerfVerdict::Inconclusive => println!("\x1b[33m⏳ {}\x1b[0m", report.inconclusive_reason()),
+    }
+}
+
+pub(crate) fn print_perf(report: &PerfReport, output: OutputMode) {
+    match output {
+        OutputMode::Ascii => print_perf_ascii(report),
+        OutputMode::Rich => print_perf_rich(report),
+        OutputMode::None => {}
+    }
+}
@@ -0,0 +1,201 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+mod display;
+mod stats;
+
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
+use clap::{Args, ValueEnum};
+
+use crate::{
+    diff::{
+        FailureKind, RunOptions, SolverOutput, SolverRun, SolverSpec, check_solvers,
+        display::{
+            CheckStatus, Outcome, OutputMode, SolverErrorType, report_failure, report_minizinc,
+            report_timeout,
+        },
+        minizinc::run_solver,
+        parse_solver_spec,
+    },
+    perf::{
+        display::{PerfReport, PerfVerdict, print_perf},
+        stats::{median_estimate, ratio_estimate},
+    },
+};
+
+#[derive(Args, Debug)]
+pub(crate) struct PerfArgs {
+    /// MiniZinc model file
+    model: PathBuf,
+    /// MiniZinc data file
+    instance: PathBuf,
+    /// Solver tag used as the baseline (displayed on the left), followed by --flags if needed
+    #[arg(value_parser = parse_solver_spec)]
+    solver_left: SolverSpec,
+    /// Solver tag checked against the baseline (displayed on the right), followed by --flags if needed
+    #[arg(value_parser = parse_solver_spec)]
+    solver_right: SolverSpec,
+    /// Number of runs of each solver
+    #[arg(short, long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    runs: u64,
+    /// Timeout for every run
+    #[arg(short, long)]
+    timeout_secs: Option<u64>,
+    /// Measurement compared between the solvers
+    #[arg(short, long, value_enum, default_value_t = Metric::Wall)]
+    metric: Metric,
+    /// Largest tolerated ratio of the right median to the left one
+    #[arg(long, default_value_t = 1.1, value_parser = parse_max_slowdown)]
+    max_slowdown: f64,
+    /// Confidence level of the reported intervals, between 0 and 1
+    #[arg(long, default_value_t = 0.95, value_parser = parse_confidence)]
+    confidence: f64,
+    /// Disables all output
+    #[arg(short, long)]
+    quiet: bool,
+    /// `minizinc` executable used by the solvers whose spec does not give one with `tag@path`
+    #[arg(long, value_name = "PATH")]
+    minizinc: Option<PathBuf>,
+    /// Logs the command line, output, exit status and timestamps of every run to this directory
+    #[arg(long, value_name = "DIR")]
+    log_dir: Option<PathBuf>,
+}
+
+fn parse_max_slowdown(s: &str) -> Result<f64, String> {
+    let factor: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    if factor.is_finite() && factor > 0.0 {
+        Ok(factor)
+    } else {
+        Err("the slowdown factor must be a finite positive number".to_string())
+    }
+}
+
+fn parse_confidence(s: &str) -> Result<f64, String> {
+    let confidence: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    if confidence > 0.0 && confidence < 1.0 {
+        Ok(confidence)
+    } else {
+        Err("the confidence level must be strictly between 0 and 1, e.g. 0.95".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum Metric {
+    /// Wall-clock time
+    Wall,
+    /// User and system CPU time of the solver process tree
+    Cpu,
+    /// Peak resident set size of the solver process tree
+    Memory,
+}
+
+impl Metric {
+    fn measure(self, run: &SolverRun) -> f64 {
+        match self {
+            Metric::Wall => run.duration.as_secs_f64(),
+            Metric::Cpu => (run.usage.user_time + run.usage.system_time).as_secs_f64(),
+            Metric::Memory => run.usage.max_rss as f64,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Metric::Wall => "time",
+            Metric::Cpu => "CPU time",
+            Metric::Memory => "peak memory",
+        }
+    }
+
+    pub(crate) fn format(self, value: f64) -> String {
+        match self {
+            Metric::Wall | Metric::Cpu => format!("{value:.3}s"),
+            Metric::Memory => format!("{:.1}MiB", value / (1024.0 * 1024.0)),
+        }
+    }
+
+    /// Adjectives describing a higher and a lower value of the metric.
+    pub(crate) fn comparatives(self) -> (&'static str, &'static str) {
+        match self {
+            Metric::Wall | Metric::Cpu => ("slower", "faster"),
+            Metric::Memory => ("more memory-hungry", "less memory-hungry"),
+        }
+    }
+}
+
+pub(crate) fn run(mut args: PerfArgs) -> ExitCode {
+    args.solver_left.default_minizinc(args.minizinc.as_deref());
+    args.solver_right.default_minizinc(args.minizinc.as_deref());
+    let output_mode = OutputMode::new(args.quiet);
+    if let Err(status) = check_solvers(&mut args.solver_left, &mut args.solver_right, output_mode) {
+        return status.into();
+    }
+    report_minizinc(&args.solver_left, &args.solver_right, output_mode);
+    let options = RunOptions {
+        timeout: args.timeout_secs.map(Duration::from_secs),
+        log_dir: args.log_dir.clone(),
+        ..Default::default()
+    };
+    let mut samples_left = Vec::new();
+    let mut samples_right = Vec::new();
+
+    // Runs are sequential so that the solvers do not compete for the same cores, and
+    // interleaved so that a drift in the machine load affects both solvers alike.
+    for _ in 0..args.runs {
+        for (solver, samples, side) in [
+            (&args.solver_left, &mut samples_left, SolverErrorType::Left),
+            (
+                &args.solver_right,
+                &mut samples_right,
+                SolverErrorType::Right,
+            ),
+        ] {
+            match run_solver(
+                args.model.clone(),
+                args.instance.clone(),
+                solver.clone(),
+                &options,
+            ) {
+                Ok(SolverOutput::Complete(run)) => samples.push(args.metric.measure(&run)),
+                Ok(SolverOutput::Timeout(_)) => {
+                    report_timeout(side, output_mode);
+                    return CheckStatus::failure(FailureKind::Timeout, side).into();
+                }
+                Ok(SolverOutput::Failed(e, _)) | Err(e) => {
+                    return report_failure(e, side, output_mode).into();
+                }
+            }
+        }
+    }
+
+    let report = PerfReport {
+        left: median_estimate(&samples_left, args.confidence),
+        right: median_estimate(&samples_right, args.confidence),
+        factor: ratio_estimate(&samples_left, &samples_right, args.confidence),
+        n_runs: samples_left.len(),
+        metric: args.metric,
+        confidence: args.confidence,
+        max_slowdown: args.max_slowdown,
+    };
+    print_perf(&report, output_mode);
+    match report.verdict() {
+        PerfVerdict::Regression(_) => CheckStatus {
+            right: Outcome::Slowdown,
+            ..CheckStatus::SUCCESS
+        }
+        .into(),
+        _ => CheckStatus::SUCCESS.into(),
+    }
+}
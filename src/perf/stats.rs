@@ -0,0 +1,139 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+use rand::prelude::*;
+
+/// Number of bootstrap resamples used to estimate confidence intervals.
+const N_RESAMPLES: usize = 10_000;
+
+/// A point estimate together with its confidence interval.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Estimate {
+    pub(crate) value: f64,
+    pub(crate) low: f64,
+    pub(crate) high: f64,
+}
+
+pub(crate) fn median(samples: &[f64]) -> f64 {
+    assert!(!samples.is_empty());
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+fn resample(samples: &[f64], rng: &mut SmallRng) -> Vec<f64> {
+    (0..samples.len())
+        .map(|_| samples[rng.random_range(0..samples.len())])
+        .collect()
+}
+
+/// Percentile bootstrap: the confidence interval is read off the sorted statistics of the
+/// resamples.
+fn bootstrap(
+    value: f64,
+    confidence: f64,
+    mut statistic: impl FnMut(&mut SmallRng) -> f64,
+) -> Estimate {
+    // The seed is fixed so that the same measurements always produce the same report.
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut stats: Vec<f64> = (0..N_RESAMPLES).map(|_| statistic(&mut rng)).collect();
+    stats.sort_by(f64::total_cmp);
+    let alpha = (1.0 - confidence) / 2.0;
+    let low_index = ((N_RESAMPLES as f64) * alpha).floor() as usize;
+    let high_index = ((N_RESAMPLES as f64) * (1.0 - alpha)).ceil() as usize;
+    Estimate {
+        value,
+        low: stats[low_index.min(N_RESAMPLES - 1)],
+        high: stats[high_index.min(N_RESAMPLES - 1)],
+    }
+}
+
+/// Median of the samples with its bootstrap confidence interval.
+pub(crate) fn median_estimate(samples: &[f64], confidence: f64) -> Estimate {
+    bootstrap(median(samples), confidence, |rng| {
+        median(&resample(samples, rng))
+    })
+}
+
+/// Ratio of two medians, where two zeros are taken as equal.
+fn ratio(right: f64, left: f64) -> f64 {
+    if left == 0.0 {
+        if right == 0.0 { 1.0 } else { f64::INFINITY }
+    } else {
+        right / left
+    }
+}
+
+/// Ratio of the median of `right` to the median of `left`, i.e. the slowdown factor of the
+/// right solver, with its bootstrap confidence interval. There is no ratio if the median of
+/// `left` is zero, e.g. for runs too short to be measured, unless both medians are.
+pub(crate) fn ratio_estimate(left: &[f64], right: &[f64], confidence: f64) -> Option<Estimate> {
+    let (median_left, median_right) = (median(left), median(right));
+    if median_left == 0.0 && median_right != 0.0 {
+        return None;
+    }
+    Some(bootstrap(
+        ratio(median_right, median_left),
+        confidence,
+        |rng| ratio(median(&resample(right, rng)), median(&resample(left, rng))),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_odd_and_even_samples() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+        assert_eq!(median(&[7.0]), 7.0);
+    }
+
+    #[test]
+    fn median_estimate_brackets_the_median() {
+        let samples = [1.0, 1.1, 0.9, 1.05, 0.95, 1.2, 0.8];
+        let estimate = median_estimate(&samples, 0.95);
+        assert_eq!(estimate.value, 1.0);
+        assert!(estimate.low <= estimate.value && estimate.value <= estimate.high);
+    }
+
+    #[test]
+    fn ratio_estimate_of_a_slowdown() {
+        let left = [1.0, 1.1, 0.9, 1.0, 1.0];
+        let right = [2.0, 2.2, 1.8, 2.0, 2.0];
+        let estimate = ratio_estimate(&left, &right, 0.95).unwrap();
+        assert_eq!(estimate.value, 2.0);
+        assert!(estimate.low > 1.0);
+    }
+
+    #[test]
+    fn ratio_estimate_with_zero_medians() {
+        let zeros = [0.0, 0.0, 0.0];
+        assert_eq!(ratio_estimate(&zeros, &zeros, 0.95).unwrap().value, 1.0);
+        assert!(ratio_estimate(&zeros, &[1.0, 1.0, 1.0], 0.95).is_none());
+        assert_eq!(
+            ratio_estimate(&[1.0, 1.0, 1.0], &zeros, 0.95)
+                .unwrap()
+                .value,
+            0.0
+        );
+    }
+}
@@ -0,0 +1,339 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+mod template;
+
+use std::{
+    collections::HashSet,
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::Args;
+use serde_json::Value;
+
+use crate::{
+    diff::{Side, catalog, minizinc::parse_solutions, solution::pair_solutions},
+    report::template::Template,
+};
+
+/// Lines of the error output quoted in the issue.
+const MAX_STDERR_LINES: usize = 30;
+
+#[derive(Args, Debug)]
+pub(crate) struct ReportBugArgs {
+    /// Folder of a finding, as written by `--save-findings`
+    finding: PathBuf,
+    /// Solver the issue is about; by default the one that failed, or the left one for a mismatch
+    #[arg(long, value_enum)]
+    side: Option<Side>,
+    /// Issue template; by default picked from the solver the issue is about
+    #[arg(long, value_enum, default_value_t = Template::Auto)]
+    template: Template,
+    /// Number of example solutions shown
+    #[arg(long, default_value_t = 5)]
+    examples: usize,
+    /// Where to write the issue; by default `issue.md` in the finding folder
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+/// One side of a finding, as recorded in `finding.json`.
+struct RunRecord {
+    solver: String,
+    tag: String,
+    /// `minizinc` executable and its version
+    executable: PathBuf,
+    minizinc: String,
+    /// Identifier, name and version of the solver when the finding was saved
+    info: Option<(String, String, String)>,
+    outcome: String,
+    error: Option<String>,
+    command: String,
+    solutions: Vec<String>,
+    stderr: String,
+}
+
+impl RunRecord {
+    fn read(dir: &Path, finding: &Value, side: &str) -> io::Result<RunRecord> {
+        let record = &finding[side];
+        let field = |key: &str| record[key].as_str().unwrap_or_default().to_string();
+        let stdout = fs::read_to_string(dir.join(format!("{side}.stdout"))).unwrap_or_default();
+        let stderr = fs::read(dir.join(format!("{side}.stderr"))).unwrap_or_default();
+        Ok(RunRecord {
+            solver: field("solver"),
+            tag: field("tag"),
+            executable: record["executable"]
+                .as_str()
+                .map_or_else(|| PathBuf::from("minizinc"), PathBuf::from),
+            minizinc: record["minizinc"].as_str().unwrap_or("unknown").to_string(),
+            info: match (
+                record["solver_id"].as_str(),
+                record["solver_name"].as_str(),
+                record["solver_version"].as_str(),
+            ) {
+                (Some(id), Some(name), Some(version)) => {
+                    Some((id.to_string(), name.to_string(), version.to_string()))
+                }
+                _ => None,
+            },
+            outcome: field("outcome"),
+            error: record["error"].as_str().map(str::to_string),
+            command: field("command"),
+            solutions: parse_solutions(&stdout),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        })
+    }
+
+    fn is_complete(&self) -> bool {
+        self.outcome == "completed"
+    }
+
+    fn outcome_text(&self) -> String {
+        match &self.error {
+            Some(error) => format!("{} ({error})", self.outcome),
+            None => self.outcome.clone(),
+        }
+    }
+}
+
+/// Name, version and identifier of the solver of a run, as recorded in the finding. Findings
+/// saved without them fall back to the solvers currently known to its `minizinc`.
+fn solver_version(record: &RunRecord) -> (String, String) {
+    if let Some((id, name, version)) = &record.info {
+        return (format!("{name} {version}"), id.clone());
+    }
+    let solvers = catalog::load(&record.executable).unwrap_or_default();
+    match catalog::find(&solvers, &record.tag) {
+        Some(info) => (format!("{} {}", info.name, info.version), info.id.clone()),
+        None => (
+            format!("`{}` (version unknown)", record.tag),
+            record.tag.clone(),
+        ),
+    }
+}
+
+fn fenced(text: &str, language: &str) -> String {
+    let fence = if text.contains("```") { "~~~~" } else { "```" };
+    format!("{fence}{language}\n{}\n{fence}\n", text.trim_end())
+}
+
+fn write_issue(args: &ReportBugArgs, dir: &Path) -> io::Result<String> {
+    let finding: Value =
+        serde_json::from_slice(&fs::read(dir.join("finding.json"))?).map_err(io::Error::other)?;
+    let left = RunRecord::read(dir, &finding, "left")?;
+    let right = RunRecord::read(dir, &finding, "right")?;
+    let side = args
+        .side
+        .unwrap_or(match (left.is_complete(), right.is_complete()) {
+            (true, false) => Side::Right,
+            _ => Side::Left,
+        });
+    let (suspect, reference) = match side {
+        Side::Left => (&left, &right),
+        Side::Right => (&right, &left),
+    };
+
+    let (suspect_version, suspect_id) = solver_version(suspect);
+    let (reference_version, _) = solver_version(reference);
+    let template = args.template.resolve(&suspect_id);
+    let model_name = finding["model"].as_str().unwrap_or("model.mzn");
+    let instance_name = finding["instance"].as_str().unwrap_or("data.dzn");
+    let model = fs::read_to_string(dir.join(model_name))?;
+    let instance = fs::read_to_string(dir.join(instance_name))?;
+
+    // Missing solutions only count if the suspect finished its search, and extra ones only
+    // if the reference did.
+    let suspect_set: HashSet<&String> = suspect.solutions.iter().collect();
+    let reference_set: HashSet<&String> = reference.solutions.iter().collect();
+    let mut missing: Vec<String> = Vec::new();
+    if suspect.is_complete() {
+        missing = reference
+            .solutions
+            .iter()
+            .filter(|s| !suspect_set.contains(s))
+            .cloned()
+            .collect();
+    }
+    let mut extra: Vec<String> = Vec::new();
+    if reference.is_complete() {
+        extra = suspect
+            .solutions
+            .iter()
+            .filter(|s| !reference_set.contains(s))
+            .cloned()
+            .collect();
+    }
+    missing.sort();
+    missing.dedup();
+    extra.sort();
+    extra.dedup();
+
+    let problem = if !suspect.is_complete() {
+        suspect.outcome_text()
+    } else {
+        match (missing.is_empty(), extra.is_empty()) {
+            (false, true) => "missing solutions".to_string(),
+            (true, false) => "wrong solutions".to_string(),
+            (false, false) => "missing and wrong solutions".to_string(),
+            (true, true) => "different behaviour".to_string(),
+        }
+    };
+
+    let mut issue = String::new();
+    let mut w = |line: String| {
+        issue.push_str(&line);
+        issue.push('\n');
+    };
+    w(format!("# {suspect_version}: {problem} on `{model_name}`"));
+    w(String::new());
+    if let Some(tracker) = template.tracker() {
+        w(format!("<!-- To be filed at {tracker} -->"));
+        w(String::new());
+    }
+    w("## Description".to_string());
+    w(String::new());
+    w(format!(
+        "Running `{}` on the model and data below ends with: {}. For comparison, `{}` ({reference_version}) ends with: {}.",
+        suspect.solver,
+        problem,
+        reference.solver,
+        reference.outcome_text()
+    ));
+    w(String::new());
+    w("## Environment".to_string());
+    w(String::new());
+    if suspect_id == suspect.tag {
+        w(format!("- Solver: {suspect_version}"));
+    } else {
+        w(format!("- Solver: {suspect_version} (`{suspect_id}`)"));
+    }
+    w(format!("- Solver flags: `{}`", suspect.solver));
+    w(format!("- MiniZinc: {}", suspect.minizinc));
+    if reference.minizinc != suspect.minizinc {
+        w(format!(
+            "- MiniZinc of the reference run: {}",
+            reference.minizinc
+        ));
+    }
+    w(format!(
+        "- Platform: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    w(String::new());
+    w(format!("## Model (`{model_name}`)"));
+    w(String::new());
+    w(fenced(&model, "minizinc"));
+    w(format!("## Data (`{instance_name}`)"));
+    w(String::new());
+    w(fenced(&instance, "minizinc"));
+
+    if !missing.is_empty() || !extra.is_empty() {
+        w("## Expected vs. actual solutions".to_string());
+        w(String::new());
+        let mut counts = Vec::new();
+        if !missing.is_empty() {
+            counts.push(format!(
+                "misses {} solutions found by `{}`",
+                missing.len(),
+                reference.solver
+            ));
+        }
+        if !extra.is_empty() {
+            counts.push(format!(
+                "finds {} solutions that `{}` does not",
+                extra.len(),
+                reference.solver
+            ));
+        }
+        w(format!(
+            "`{}` {}. Lines starting with `-` are expected but missing, lines starting with `+` are found but not expected.",
+            suspect.solver,
+            counts.join(" and ")
+        ));
+        w(String::new());
+        let pairing = pair_solutions(&missing, &extra);
+        // One budget of examples for all of them, pairs first.
+        let examples = (pairing.pairs.iter())
+            .map(|(expected, actual)| format!("- {expected}\n+ {actual}"))
+            .chain(
+                pairing
+                    .removed
+                    .iter()
+                    .map(|expected| format!("- {expected}")),
+            )
+            .chain(pairing.added.iter().map(|actual| format!("+ {actual}")))
+            .take(args.examples);
+        let mut diff = String::new();
+        for example in examples {
+            let _ = writeln!(diff, "{example}");
+        }
+        w(fenced(&diff, "diff"));
+    }
+    if !suspect.is_complete() && !suspect.stderr.trim().is_empty() {
+        let lines: Vec<&str> = suspect.stderr.lines().take(MAX_STDERR_LINES).collect();
+        w("## Error output".to_string());
+        w(String::new());
+        w(fenced(&lines.join("\n"), ""));
+    }
+
+    w("## Steps to reproduce".to_string());
+    w(String::new());
+    w(format!(
+        "1. Save the model as `{model_name}` and the data as `{instance_name}`."
+    ));
+    w("2. Run:".to_string());
+    w(String::new());
+    w(fenced(&suspect.command, "sh"));
+    w("3. Compare with:".to_string());
+    w(String::new());
+    w(fenced(&reference.command, "sh"));
+
+    let checklist = template.checklist(&suspect.tag, model_name, instance_name);
+    if !checklist.is_empty() {
+        w("## Additional details".to_string());
+        w(String::new());
+        for item in checklist {
+            w(format!("- [ ] {item}"));
+        }
+    }
+    Ok(issue)
+}
+
+pub(crate) fn run(args: ReportBugArgs) -> ExitCode {
+    let issue = match write_issue(&args, &args.finding) {
+        Ok(issue) => issue,
+        Err(e) => {
+            eprintln!(
+                "Failed to read the finding in {}: {e}",
+                args.finding.display()
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let path = args
+        .output
+        .clone()
+        .unwrap_or_else(|| args.finding.join("issue.md"));
+    if let Err(e) = fs::write(&path, issue) {
+        eprintln!("Failed to write {}: {e}", path.display());
+        return ExitCode::FAILURE;
+    }
+    println!("Issue draft written to {}", path.display());
+    ExitCode::SUCCESS
+}
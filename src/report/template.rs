@@ -0,0 +1,86 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clap::ValueEnum;
+
+/// Issue template, as each solver project asks for different details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Template {
+    /// Picks the template from the solver the issue is about
+    Auto,
+    Gecode,
+    Chuffed,
+    OrTools,
+    Generic,
+}
+
+impl Template {
+    /// Resolves `Auto` from the identifier or the tag of the solver.
+    pub(crate) fn resolve(self, solver: &str) -> Template {
+        if self != Template::Auto {
+            return self;
+        }
+        let solver = solver.to_lowercase();
+        if solver.contains("gecode") {
+            Template::Gecode
+        } else if solver.contains("chuffed") {
+            Template::Chuffed
+        } else if ["ortools", "or-tools", "cp-sat", "cpsat"]
+            .iter()
+            .any(|name| solver.contains(name))
+        {
+            Template::OrTools
+        } else {
+            Template::Generic
+        }
+    }
+
+    /// Issue tracker of the solver project.
+    pub(crate) fn tracker(self) -> Option<&'static str> {
+        match self {
+            Template::Gecode => Some("https://github.com/Gecode/gecode/issues"),
+            Template::Chuffed => Some("https://github.com/chuffed/chuffed/issues"),
+            Template::OrTools => Some("https://github.com/google/or-tools/issues"),
+            Template::Auto | Template::Generic => None,
+        }
+    }
+
+    /// Details the maintainers of the solver usually ask for, as a markdown checklist.
+    pub(crate) fn checklist(self, tag: &str, model: &str, instance: &str) -> Vec<String> {
+        let flatzinc = format!(
+            "FlatZinc of the instance, from `minizinc -c --solver {tag} {model} {instance}`"
+        );
+        match self {
+            Template::Gecode => vec![
+                flatzinc,
+                "Whether the bug also shows up with `-p 1` and without `-f`".to_string(),
+                "Whether Gecode was built with or without Gist and CPProfiler support".to_string(),
+            ],
+            Template::Chuffed => vec![
+                flatzinc,
+                "Whether the bug depends on free search (`-f`)".to_string(),
+                "Whether the bug depends on lazy clause generation being enabled".to_string(),
+            ],
+            Template::OrTools => vec![
+                "Number of workers (`-p`) and whether the bug also shows up with `-p 1`"
+                    .to_string(),
+                "CP-SAT parameters passed through the solver flags".to_string(),
+                "Whether OR-Tools was installed from a release or built from source".to_string(),
+            ],
+            Template::Auto | Template::Generic => vec![flatzinc],
+        }
+    }
+}
@@ -0,0 +1,86 @@
+// minizinc-diff
+// Copyright (C) 2025 Konstantin Sidorov
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Args;
+
+use crate::diff::catalog::{self, SolverInfo};
+
+#[derive(Args, Debug)]
+pub(crate) struct SolversArgs {
+    /// `minizinc` executable whose solvers are listed
+    #[arg(long, value_name = "PATH", default_value = "minizinc")]
+    minizinc: PathBuf,
+    /// Only lists the solver selected by this tag
+    tag: Option<String>,
+}
+
+fn print_solver(solver: &SolverInfo) {
+    println!("{} {} ({})", solver.name, solver.version, solver.id);
+    if !solver.tags.is_empty() {
+        println!("  tags: {}", solver.tags.join(", "));
+    }
+    if !solver.std_flags.is_empty() {
+        println!("  standard flags: {}", solver.std_flags.join(" "));
+    }
+    if !solver.extra_flags.is_empty() {
+        println!("  extra flags:");
+        let width = solver
+            .extra_flags
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        for (name, description) in &solver.extra_flags {
+            println!("    {name:width$}  {description}");
+        }
+    }
+}
+
+pub(crate) fn run(args: SolversArgs) -> ExitCode {
+    let solvers = match catalog::load(&args.minizinc) {
+        Ok(solvers) => solvers,
+        Err(e) => {
+            eprintln!(
+                "Failed to list the solvers of {}: {e}",
+                args.minizinc.display()
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    match &args.tag {
+        Some(tag) => match catalog::find(&solvers, tag) {
+            Some(solver) => print_solver(solver),
+            None => {
+                match catalog::suggest(&solvers, tag) {
+                    Some(name) => eprintln!("No solver with tag `{tag}`, did you mean `{name}`?"),
+                    None => eprintln!("No solver with tag `{tag}`"),
+                }
+                return ExitCode::FAILURE;
+            }
+        },
+        None => {
+            for (i, solver) in solvers.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print_solver(solver);
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}